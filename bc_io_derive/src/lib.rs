@@ -0,0 +1,259 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, ToTokens};
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, Member, Type};
+
+/// The integer types that are stored in little endian byte order.
+const INTEGERS: [&str; 10] = [
+    "u8", "u16", "u32", "u64", "u128", "i8", "i16", "i32", "i64", "i128",
+];
+
+/// The paths by which the SHA-256 digest of ```bc_hash``` can be named.
+const DIGEST_PATHS: [&str; 5] = [
+    "Digest",
+    "io::Digest",
+    "bc_io::io::Digest",
+    "sha256::Digest",
+    "bc_hash::sha256::Digest",
+];
+
+/// The integer types whose size depends on the target, so they would not have a fixed layout.
+const POINTER_SIZED: [&str; 2] = ["usize", "isize"];
+
+/// How a field is transmutated to and from its bytes.
+enum Kind {
+    Integer,
+    Bytes,
+    Digest,
+}
+
+/// A field of a struct along with its type and the expression for its size in bytes.
+struct Field {
+    member: Member,
+    ty: Type,
+    kind: Kind,
+    size: TokenStream2,
+}
+
+/// Returns the kind and size of the field ```name``` of type ```ty```, or an error if it is not supported.
+fn classify(ty: &Type, name: &str) -> syn::Result<(Kind, TokenStream2)> {
+    match ty {
+        Type::Path(path) if path.qself.is_none() => {
+            let ident: String = path.path.segments.last().unwrap().ident.to_string();
+            if INTEGERS.contains(&ident.as_str()) && path.path.segments.len() == 1 {
+                Ok((Kind::Integer, quote!(::core::mem::size_of::<#ty>())))
+            } else if POINTER_SIZED.contains(&ident.as_str()) && path.path.segments.len() == 1 {
+                Err(Error::new_spanned(
+                    ty,
+                    format!(
+                        "field `{}` is a `{}`, whose size depends on the target; use a fixed size integer instead",
+                        name, ident
+                    ),
+                ))
+            } else if ident == "Digest" {
+                let path: String = path.path.to_token_stream().to_string().replace(' ', "");
+                if DIGEST_PATHS.contains(&path.trim_start_matches("::")) {
+                    // the generated code converts through ```bc_io::io::Digest```, so a different
+                    // type that is imported as ```Digest``` fails to compile with mismatched types
+                    Ok((Kind::Digest, quote!(::bc_io::io::DIGEST_SIZE)))
+                } else {
+                    Err(Error::new_spanned(
+                        ty,
+                        format!(
+                            "field `{}` has type `{}`, but the only supported digest is `bc_io::io::Digest`",
+                            name, path
+                        ),
+                    ))
+                }
+            } else {
+                Err(Error::new_spanned(
+                    ty,
+                    format!("field `{}` has an unsupported type", name),
+                ))
+            }
+        }
+        Type::Array(array) if array.elem.to_token_stream().to_string() == "u8" => {
+            let len: &syn::Expr = &array.len;
+            Ok((Kind::Bytes, quote!((#len))))
+        }
+        _ => Err(Error::new_spanned(
+            ty,
+            format!(
+                "field `{}` has an unsupported type; only integers, byte arrays, and Digest fields are supported",
+                name
+            ),
+        )),
+    }
+}
+
+/// Returns the fields of the struct in ```input```, in the order they are laid out.
+fn fields(input: &DeriveInput) -> syn::Result<Vec<Field>> {
+    let data: &syn::DataStruct = match &input.data {
+        Data::Struct(data) => data,
+        _ => return Err(Error::new_spanned(input, "only structs are supported")),
+    };
+    let fields: Vec<&syn::Field> = match &data.fields {
+        Fields::Named(fields) => fields.named.iter().collect(),
+        Fields::Unnamed(fields) => fields.unnamed.iter().collect(),
+        Fields::Unit => Vec::new(),
+    };
+    fields
+        .into_iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let member: Member = match &field.ident {
+                Some(ident) => Member::Named(ident.clone()),
+                None => Member::Unnamed(i.into()),
+            };
+            let name: String = member.to_token_stream().to_string();
+            let (kind, size) = classify(&field.ty, &name)?;
+            Ok(Field {
+                member,
+                ty: field.ty.clone(),
+                kind,
+                size,
+            })
+        })
+        .collect()
+}
+
+/// Returns the expression for the offset of each field followed by the total size of the struct.
+fn offsets(fields: &[Field]) -> Vec<TokenStream2> {
+    let mut offsets: Vec<TokenStream2> = vec![quote!(0usize)];
+    for field in fields {
+        let prev: &TokenStream2 = offsets.last().unwrap();
+        let size: &TokenStream2 = &field.size;
+        offsets.push(quote!(#prev + #size));
+    }
+    offsets
+}
+
+/// Derives ```bc_io::io::Serialize``` for a struct of integers, byte arrays, and ```Digest```
+/// fields, which are laid out in declaration order with integers in little endian byte order.
+/// Also defines the constant ```SIZE```, the size of the data section in bytes. A field whose
+/// type is named ```Digest``` must be ```bc_io::io::Digest```, the SHA-256 digest of ```bc_hash```;
+/// any other type of that name is rejected.
+#[proc_macro_derive(Serialize)]
+pub fn derive_serialize(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = parse_macro_input!(input as DeriveInput);
+    match serialize(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// Derives ```bc_io::io::Deserialize``` for a struct with the same layout as ```Serialize```.
+#[proc_macro_derive(Deserialize)]
+pub fn derive_deserialize(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = parse_macro_input!(input as DeriveInput);
+    match deserialize(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// Generates the ```SIZE``` constant and ```Serialize``` implementation for ```input```.
+fn serialize(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name: &syn::Ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let fields: Vec<Field> = fields(input)?;
+    let offsets: Vec<TokenStream2> = offsets(&fields);
+    let size: &TokenStream2 = offsets.last().unwrap();
+    let writes = fields.iter().zip(offsets.iter()).map(|(field, offset)| {
+        let member: &Member = &field.member;
+        let end: TokenStream2 = {
+            let size: &TokenStream2 = &field.size;
+            quote!(#offset + #size)
+        };
+        match field.kind {
+            Kind::Integer => quote! {
+                buf[#offset..#end].copy_from_slice(&self.#member.to_le_bytes());
+            },
+            Kind::Bytes => quote! {
+                buf[#offset..#end].copy_from_slice(&self.#member[..]);
+            },
+            Kind::Digest => quote! {
+                ::bc_io::io::Digest::serialize(&self.#member, &mut buf[#offset..#end])?;
+            },
+        }
+    });
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// The size of the serialized struct in bytes.
+            pub const SIZE: usize = #size;
+        }
+
+        impl #impl_generics ::bc_io::io::Serialize for #name #ty_generics #where_clause {
+            fn serialize(&self, buf: &mut [u8]) -> ::bc_io::io::Result<()> {
+                if buf.len() != Self::SIZE {
+                    Err(::bc_io::io::Error::InvalidSliceLength)
+                } else {
+                    #(#writes)*
+                    Ok(())
+                }
+            }
+        }
+    })
+}
+
+/// Generates the ```Deserialize``` implementation for ```input```.
+fn deserialize(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name: &syn::Ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let fields: Vec<Field> = fields(input)?;
+    let offsets: Vec<TokenStream2> = offsets(&fields);
+    let size: &TokenStream2 = offsets.last().unwrap();
+    let reads = fields.iter().zip(offsets.iter()).map(|(field, offset)| {
+        let member: &Member = &field.member;
+        let ty: &Type = &field.ty;
+        let size: &TokenStream2 = &field.size;
+        let value: TokenStream2 = match field.kind {
+            Kind::Integer => quote! {
+                <#ty>::from_le_bytes(buf[#offset..#offset + #size].try_into().unwrap())
+            },
+            Kind::Bytes => quote! {
+                buf[#offset..#offset + #size].try_into().unwrap()
+            },
+            Kind::Digest => quote! {
+                ::bc_io::io::Digest::deserialize(&buf[#offset..#offset + #size])?
+            },
+        };
+        quote!(#member: #value)
+    });
+    Ok(quote! {
+        impl #impl_generics ::bc_io::io::Deserialize for #name #ty_generics #where_clause {
+            fn deserialize(buf: &[u8]) -> ::bc_io::io::Result<Self>
+            where
+                Self: Sized,
+            {
+                if buf.len() != #size {
+                    Err(::bc_io::io::Error::InvalidSliceLength)
+                } else {
+                    Ok(Self { #(#reads),* })
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,241 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+use crate::io::{Error, Result};
+use crate::shared::ChainFile;
+use bc_hash::sha256::Digest;
+use futures_core::Stream;
+use std::future::Future;
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::task::{self, JoinError, JoinHandle};
+
+/// Runs ```f``` on ```chain``` on the blocking thread pool of the tokio runtime, so that the file
+/// IO does not stall the runtime.
+async fn blocking<T, F>(chain: &Arc<ChainFile>, f: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce(&ChainFile) -> Result<T> + Send + 'static,
+{
+    let chain: Arc<ChainFile> = chain.clone();
+    task::spawn_blocking(move || f(&chain))
+        .await
+        .unwrap_or_else(|e| Err(join_error(e)))
+}
+
+/// Returns the error for a blocking task that panicked or was cancelled.
+fn join_error(e: JoinError) -> Error {
+    Error::from(std::io::Error::other(e))
+}
+
+/// An asynchronous reader of a shared blockchain file. Each read is performed on the blocking
+/// thread pool of the tokio runtime with the same semantics as ```Reader```. Any number of readers
+/// may share the same file, including while an ```AsyncWriter``` is appending to it.
+#[derive(Debug, Clone)]
+pub struct AsyncReader {
+    chain: Arc<ChainFile>,
+}
+
+impl AsyncReader {
+    /// Creates and returns a new ```AsyncReader``` for ```chain```.
+    pub fn new(chain: Arc<ChainFile>) -> Self {
+        Self { chain }
+    }
+
+    /// Opens the existing blockchain file at ```path``` and returns a new ```AsyncReader``` for it.
+    pub async fn open_existing(path: &Path) -> Result<AsyncReader> {
+        let path: std::path::PathBuf = path.to_path_buf();
+        let chain: ChainFile = task::spawn_blocking(move || ChainFile::open_existing(&path))
+            .await
+            .unwrap_or_else(|e| Err(join_error(e)))?;
+        Ok(Self::new(Arc::new(chain)))
+    }
+
+    /// Returns the shared blockchain file that blocks are read from.
+    #[inline]
+    pub fn chain(&self) -> &Arc<ChainFile> {
+        &self.chain
+    }
+
+    /// Returns the block size of the underlying blockchain file.
+    #[inline]
+    pub fn block_size(&self) -> usize {
+        self.chain.block_size()
+    }
+
+    /// Returns the size of the data section of each block in bytes.
+    #[inline]
+    pub fn data_size(&self) -> usize {
+        self.chain.data_size()
+    }
+
+    /// Returns the number of blocks that have been completely written.
+    #[inline]
+    pub fn block_count(&self) -> u64 {
+        self.chain.block_count()
+    }
+
+    /// Reads and returns the entire block located at ```index```.
+    pub async fn read_block_at(&self, index: u64) -> Result<Vec<u8>> {
+        blocking(&self.chain, move |chain| {
+            let mut buf: Vec<u8> = vec![0; chain.block_size()];
+            chain.read_block_at(index, &mut buf)?;
+            Ok(buf)
+        })
+        .await
+    }
+
+    /// Reads and returns the data section of the block located at ```index```.
+    pub async fn read_data_at(&self, index: u64) -> Result<Vec<u8>> {
+        blocking(&self.chain, move |chain| {
+            let mut buf: Vec<u8> = vec![0; chain.data_size()];
+            chain.read_data_at(index, &mut buf)?;
+            Ok(buf)
+        })
+        .await
+    }
+
+    /// Validates the block located at ```index``` the same way as ```Reader::validate_block_at()```.
+    pub async fn validate_block_at(&self, index: u64) -> Result<()> {
+        blocking(&self.chain, move |chain| chain.validate_block_at(index)).await
+    }
+
+    /// Validates every block the same way as ```Reader::validate_all_blocks()```.
+    pub async fn validate_all_blocks(&self) -> Result<()> {
+        blocking(&self.chain, |chain| chain.validate_all_blocks()).await
+    }
+
+    /// Returns a stream of every block in the chain, starting at the genisis block.
+    pub fn blocks(&self) -> Blocks {
+        self.blocks_from(0)
+    }
+
+    /// Returns a stream of the blocks in the chain starting at ```index```. The stream ends at the
+    /// last block that has been completely written when it gets there, so it includes blocks that
+    /// are appended while it is being read.
+    pub fn blocks_from(&self, index: u64) -> Blocks {
+        Blocks {
+            chain: self.chain.clone(),
+            index,
+            pending: None,
+        }
+    }
+}
+
+/// An asynchronous stream of the blocks of a shared blockchain file. Each item is the index of a
+/// block together with the entire block.
+#[derive(Debug)]
+pub struct Blocks {
+    chain: Arc<ChainFile>,
+    index: u64,
+    pending: Option<JoinHandle<Result<Vec<u8>>>>,
+}
+
+impl Blocks {
+    /// Returns the index of the next block to be returned.
+    #[inline]
+    pub fn index(&self) -> u64 {
+        self.index
+    }
+}
+
+impl Stream for Blocks {
+    type Item = Result<(u64, Vec<u8>)>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.pending.is_none() {
+            if self.index >= self.chain.block_count() {
+                return Poll::Ready(None);
+            }
+            let chain: Arc<ChainFile> = self.chain.clone();
+            let index: u64 = self.index;
+            self.pending = Some(task::spawn_blocking(move || {
+                let mut buf: Vec<u8> = vec![0; chain.block_size()];
+                chain.read_block_at(index, &mut buf)?;
+                Ok(buf)
+            }));
+        }
+        let result: Result<Vec<u8>> = match Pin::new(self.pending.as_mut().unwrap()).poll(cx) {
+            Poll::Pending => return Poll::Pending,
+            Poll::Ready(result) => result.unwrap_or_else(|e| Err(join_error(e))),
+        };
+        self.pending = None;
+        let index: u64 = self.index;
+        match result {
+            Ok(buf) => {
+                self.index += 1;
+                Poll::Ready(Some(Ok((index, buf))))
+            }
+            Err(e) => {
+                // end the stream after an error, rather than failing on the same block forever
+                self.index = u64::MAX;
+                Poll::Ready(Some(Err(e)))
+            }
+        }
+    }
+}
+
+/// An asynchronous writer of a shared blockchain file. Each append is performed on the blocking
+/// thread pool of the tokio runtime with the same semantics as ```Writer```.
+#[derive(Debug)]
+pub struct AsyncWriter {
+    chain: Arc<ChainFile>,
+}
+
+impl AsyncWriter {
+    /// Creates and returns a new ```AsyncWriter``` for ```chain```. No other writer may append to
+    /// ```chain``` while it is in use.
+    pub fn new(chain: Arc<ChainFile>) -> Self {
+        Self { chain }
+    }
+
+    /// Returns the shared blockchain file that blocks are appended to.
+    #[inline]
+    pub fn chain(&self) -> &Arc<ChainFile> {
+        &self.chain
+    }
+
+    /// Returns a new ```AsyncReader``` for the same blockchain file.
+    pub fn reader(&self) -> AsyncReader {
+        AsyncReader::new(self.chain.clone())
+    }
+
+    /// Returns the number of blocks that have been completely written.
+    #[inline]
+    pub fn block_count(&self) -> u64 {
+        self.chain.block_count()
+    }
+
+    /// Returns the hash of the last block in the stream. The hash is guarded by the same lock that
+    /// is held while a block is appended, so it is read on the blocking thread pool.
+    pub async fn last_hash(&self) -> Result<Digest> {
+        blocking(&self.chain, |chain| Ok(chain.last_hash())).await
+    }
+
+    /// Writes a new block containing ```data``` to the end of the stream and returns its index.
+    /// The length of ```data``` must be exactly equal to the size of the data section.
+    pub async fn append(&mut self, data: Vec<u8>) -> Result<u64> {
+        blocking(&self.chain, move |chain| chain.append(&data)).await
+    }
+}
@@ -0,0 +1,98 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+// Prints the last blocks of a blockchain file, one per line, as the block index, the block
+// hash, and the data section in hex. With ```-f``` it keeps printing blocks as they are
+// appended, like ```tail -f```.
+//
+// Usage: bc_tail [-f] [-n <count>] <path>
+use bc_hash::sha256::DIGEST_SIZE;
+use bc_io::export::Encoding;
+use bc_io::follow::Follow;
+use bc_io::io::{File, Reader, Result as BcResult};
+use std::path::Path;
+use std::process::exit;
+
+const USAGE: &str = "Usage: bc_tail [-f] [-n <count>] <path>";
+
+/// Prints a single block, decoding its hash and data section with ```reader```.
+fn print_block(reader: &Reader, index: u64, block: &[u8]) -> BcResult<()> {
+    let mut hash: [u8; DIGEST_SIZE] = [0; DIGEST_SIZE];
+    reader.block_hash(block)?.serialize(&mut hash)?;
+    let mut data: Vec<u8> = vec![0; reader.data_size()];
+    reader.decode_data(block, &mut data)?;
+    println!(
+        "{}\t{}\t{}",
+        index,
+        Encoding::Hex.encode(&hash),
+        Encoding::Hex.encode(&data)
+    );
+    Ok(())
+}
+
+fn main() -> BcResult<()> {
+    let mut follow: bool = false;
+    let mut count: u64 = 10;
+    let mut path: Option<String> = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-f" => follow = true,
+            "-n" => match args.next().and_then(|n| n.parse().ok()) {
+                Some(n) => count = n,
+                None => {
+                    eprintln!("{}", USAGE);
+                    exit(1);
+                }
+            },
+            _ if path.is_none() && !arg.starts_with('-') => path = Some(arg),
+            _ => {
+                eprintln!("{}", USAGE);
+                exit(1);
+            }
+        }
+    }
+    let path: String = match path {
+        Some(path) => path,
+        None => {
+            eprintln!("{}", USAGE);
+            exit(1);
+        }
+    };
+
+    let mut file: File = File::open_growing(Path::new(&path))?;
+    let mut reader: Reader = Reader::new(&mut file);
+    let end: u64 = reader.stream_size()? / reader.block_size() as u64;
+    let mut buf: Vec<u8> = vec![0; reader.block_size()];
+    for index in end.saturating_sub(count)..end {
+        reader.read_block_at(index, &mut buf)?;
+        print_block(&reader, index, &buf)?;
+    }
+    if follow {
+        let mut follow: Follow = reader.follow_from(end);
+        while let Some(block) = follow.next() {
+            let (index, block) = block?;
+            print_block(follow.reader(), index, &block)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,367 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+use crate::io::{Error, Reader, Result};
+use bc_hash::sha256::{Digest, DIGEST_SIZE};
+use std::ffi::OsString;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+/// The size of the group size, filter size, and hash count at the start of the sidecar file.
+const HEADER_SIZE: usize = 8 + 4 + 4;
+
+/// A function that extracts the key of a block from its data section.
+type KeyFn = Box<dyn Fn(&[u8]) -> Result<Vec<u8>>>;
+
+/// A bloom filter over a set of keys. ```contains()``` never returns false for a key that was
+/// inserted, but may return true for a key that was not.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BloomFilter {
+    bits: Vec<u8>,
+    hashes: u32,
+}
+
+impl BloomFilter {
+    /// Returns a new empty bloom filter of ```size``` bytes that sets ```hashes``` bits per key.
+    pub fn new(size: usize, hashes: u32) -> Self {
+        Self {
+            bits: vec![0; size.max(1)],
+            hashes: hashes.max(1),
+        }
+    }
+
+    /// Returns a new empty bloom filter sized for ```keys``` keys using ```bits_per_key``` bits
+    /// each, with the number of hashes that minimizes the false positive rate. Returns
+    /// Err(Error::IntegerOverflow) if the filter would be too large.
+    pub fn with_capacity(keys: u64, bits_per_key: u32) -> Result<Self> {
+        let size: u64 = keys
+            .checked_mul(bits_per_key as u64)
+            .ok_or(Error::IntegerOverflow)?
+            .div_ceil(8);
+        let size: usize = usize::try_from(size).map_err(|_| Error::IntegerOverflow)?;
+        let hashes: u32 = (bits_per_key as f64 * std::f64::consts::LN_2).round() as u32;
+        Ok(Self::new(size, hashes.min(16)))
+    }
+
+    /// Returns the size of the filter in bytes.
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.bits.len()
+    }
+
+    /// Returns the number of bits that are set per key.
+    #[inline]
+    pub fn hashes(&self) -> u32 {
+        self.hashes
+    }
+
+    /// Returns the bits of the filter.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bits
+    }
+
+    /// Returns a bloom filter whose bits are ```bits```, which sets ```hashes``` bits per key.
+    pub fn from_bytes(bits: &[u8], hashes: u32) -> Result<Self> {
+        if bits.is_empty() || hashes == 0 {
+            Err(Error::InvalidSliceLength)
+        } else {
+            Ok(Self {
+                bits: bits.to_vec(),
+                hashes,
+            })
+        }
+    }
+
+    /// Returns the positions of the bits for ```key```, by double hashing its SHA-256 digest.
+    fn positions(&self, key: &[u8]) -> impl Iterator<Item = usize> {
+        let mut digest: [u8; DIGEST_SIZE] = [0; DIGEST_SIZE];
+        Digest::from(key).serialize(&mut digest).unwrap();
+        let h1: u64 = u64::from_le_bytes(digest[0..8].try_into().unwrap());
+        let h2: u64 = u64::from_le_bytes(digest[8..16].try_into().unwrap());
+        let bit_count: u64 = self.bits.len() as u64 * 8;
+        (0..self.hashes as u64)
+            .map(move |i| (h1.wrapping_add(i.wrapping_mul(h2)) % bit_count) as usize)
+    }
+
+    /// Adds ```key``` to the filter.
+    pub fn insert(&mut self, key: &[u8]) {
+        for pos in self.positions(key).collect::<Vec<usize>>() {
+            self.bits[pos / 8] |= 1 << (pos % 8);
+        }
+    }
+
+    /// Returns false if ```key``` is definitely not in the filter.
+    pub fn contains(&self, key: &[u8]) -> bool {
+        self.positions(key)
+            .all(|pos| self.bits[pos / 8] & (1 << (pos % 8)) != 0)
+    }
+
+    /// Clears every bit of the filter.
+    pub fn clear(&mut self) {
+        self.bits.fill(0);
+    }
+}
+
+/// A set of bloom filters over a key extracted from the data section of each block, one per
+/// group of ```group_size``` consecutive blocks, so that a key lookup only reads the groups whose
+/// filter may contain the key. The filters of complete groups are kept in a sidecar file next to
+/// the chain, each followed by the hash of the last block of its group, while the filter of the
+/// last, partial group is rebuilt from the chain when opened.
+/// For a segmented chain, a group size equal to the number of blocks per segment gives one filter
+/// per segment.
+pub struct BloomIndex {
+    path: PathBuf,
+    file: fs::File,
+    extract: KeyFn,
+    group_size: u64,
+    filters: Vec<BloomFilter>,
+    last: BloomFilter,
+    last_hash: Option<Digest>,
+    count: u64,
+}
+
+impl std::fmt::Debug for BloomIndex {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.debug_struct("BloomIndex")
+            .field("path", &self.path)
+            .field("group_size", &self.group_size)
+            .field("filters", &self.filters.len())
+            .field("count", &self.count)
+            .finish()
+    }
+}
+
+impl BloomIndex {
+    /// Returns the path of the sidecar file of the bloom index called ```name``` of the chain at ```chain```.
+    pub fn path(chain: &Path, name: &str) -> PathBuf {
+        let mut path: OsString = chain.as_os_str().to_os_string();
+        path.push(format!(".{}.bloom", name));
+        PathBuf::from(path)
+    }
+
+    /// Opens the bloom index called ```name``` of the chain read by ```reader```, creating its sidecar
+    /// file if it does not exist, and adds any blocks appended since it was last updated. Each group
+    /// of ```group_size``` blocks gets a filter with ```bits_per_key``` bits per block. ```extract```
+    /// must return the same key for a block every time the index is opened. If the sidecar file does
+    /// not match the chain or was created with different parameters, the index is rebuilt. Returns
+    /// Err(Error::IntegerOverflow) if the filters would be too large.
+    pub fn open<F>(
+        reader: &mut Reader,
+        name: &str,
+        group_size: u64,
+        bits_per_key: u32,
+        extract: F,
+    ) -> Result<BloomIndex>
+    where
+        F: Fn(&[u8]) -> Result<Vec<u8>> + 'static,
+    {
+        if group_size == 0 || bits_per_key == 0 {
+            return Err(Error::InvalidSliceLength);
+        }
+        let last: BloomFilter = BloomFilter::with_capacity(group_size, bits_per_key)?;
+        if last.size() > u32::MAX as usize {
+            return Err(Error::IntegerOverflow);
+        }
+        let path: PathBuf = Self::path(reader.path(), name);
+        let file: fs::File = fs::File::options()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(&path)?;
+        let mut index: BloomIndex = Self {
+            path,
+            file,
+            extract: Box::new(extract),
+            group_size,
+            filters: Vec::new(),
+            last,
+            last_hash: None,
+            count: 0,
+        };
+        if !index.load()? || index.count > reader.block_count()? || !index.matches_last(reader)? {
+            index.rebuild(reader)?;
+        } else {
+            index.update(reader)?;
+        }
+        Ok(index)
+    }
+
+    /// Returns the header of the sidecar file.
+    fn header(&self) -> [u8; HEADER_SIZE] {
+        let mut header: [u8; HEADER_SIZE] = [0; HEADER_SIZE];
+        header[0..8].copy_from_slice(&self.group_size.to_le_bytes());
+        header[8..12].copy_from_slice(&(self.last.size() as u32).to_le_bytes());
+        header[12..16].copy_from_slice(&self.last.hashes().to_le_bytes());
+        header
+    }
+
+    /// Loads the filters of the sidecar file. A partially written last filter is discarded. Returns
+    /// false if the header does not match the parameters of the index.
+    fn load(&mut self) -> Result<bool> {
+        let mut buf: Vec<u8> = Vec::new();
+        (&self.file).read_to_end(&mut buf)?;
+        if buf.is_empty() {
+            self.file.write_all(&self.header())?;
+            return Ok(true);
+        } else if buf.len() < HEADER_SIZE || buf[0..HEADER_SIZE] != self.header() {
+            return Ok(false);
+        }
+        let size: usize = self.last.size();
+        let mut pos: usize = HEADER_SIZE;
+        while pos + size + DIGEST_SIZE <= buf.len() {
+            self.filters.push(BloomFilter::from_bytes(
+                &buf[pos..pos + size],
+                self.last.hashes(),
+            )?);
+            self.last_hash = Some(Digest::deserialize(
+                &buf[pos + size..pos + size + DIGEST_SIZE],
+            )?);
+            self.count += self.group_size;
+            pos += size + DIGEST_SIZE;
+        }
+        if pos != buf.len() {
+            self.file.set_len(pos as u64)?;
+        }
+        Ok(true)
+    }
+
+    /// Returns true if the hash of the last block of the last complete group is still the hash of
+    /// that block in the chain read by ```reader```. Since every block links to the one before it,
+    /// this also holds for the blocks of every earlier group.
+    fn matches_last(&self, reader: &mut Reader) -> Result<bool> {
+        let last_hash: &Digest = match &self.last_hash {
+            Some(last_hash) => last_hash,
+            None => return Ok(true),
+        };
+        let mut block: Vec<u8> = vec![0; reader.block_size()];
+        reader.read_block_at(self.count - 1, &mut block)?;
+        Ok(reader.block_hash(&block)? == *last_hash)
+    }
+
+    /// Returns the number of blocks that have been added.
+    #[inline]
+    pub fn len(&self) -> u64 {
+        self.count
+    }
+
+    /// Returns true if no blocks have been added.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Returns the number of blocks in each group.
+    #[inline]
+    pub fn group_size(&self) -> u64 {
+        self.group_size
+    }
+
+    /// Returns false if no block that has been added has the key ```key```, without reading the chain.
+    pub fn may_contain(&self, key: &[u8]) -> bool {
+        self.groups(key).next().is_some()
+    }
+
+    /// Returns the indexes of the groups whose filter may contain ```key```, in ascending order.
+    fn groups<'a>(&'a self, key: &'a [u8]) -> impl Iterator<Item = u64> + 'a {
+        let partial: Option<&BloomFilter> =
+            (!self.count.is_multiple_of(self.group_size)).then_some(&self.last);
+        self.filters
+            .iter()
+            .chain(partial)
+            .enumerate()
+            .filter(move |(_, filter)| filter.contains(key))
+            .map(|(group, _)| group as u64)
+    }
+
+    /// Adds ```block```, the block located at ```index``` in the chain read by ```reader```, to
+    /// the index. Blocks must be added in order, so ```index``` must be exactly equal to ```len()```.
+    fn insert(&mut self, reader: &Reader, index: u64, block: &[u8], data: &mut [u8]) -> Result<()> {
+        reader.decode_data(block, data)?;
+        let key: Vec<u8> = (self.extract)(data)?;
+        self.last.insert(&key);
+        self.count = index + 1;
+        if self.count.is_multiple_of(self.group_size) {
+            let last_hash: Digest = reader.block_hash(block)?;
+            let mut hash: [u8; DIGEST_SIZE] = [0; DIGEST_SIZE];
+            last_hash.serialize(&mut hash)?;
+            self.file.write_all(self.last.as_bytes())?;
+            self.file.write_all(&hash)?;
+            self.filters.push(self.last.clone());
+            self.last_hash = Some(last_hash);
+            self.last.clear();
+        }
+        Ok(())
+    }
+
+    /// Adds every block read by ```reader``` that has not been added yet.
+    /// Returns the number of blocks that were added.
+    pub fn update(&mut self, reader: &mut Reader) -> Result<u64> {
+        let block_count: u64 = reader.block_count()?;
+        let start: u64 = self.count;
+        let mut block: Vec<u8> = vec![0; reader.block_size()];
+        let mut data: Vec<u8> = vec![0; reader.data_size()];
+        if start < block_count {
+            reader.seek(start)?;
+        }
+        for index in start..block_count {
+            reader.read_block(&mut block)?;
+            self.insert(reader, index, &block, &mut data)?;
+        }
+        self.file.flush()?;
+        Ok(block_count - start)
+    }
+
+    /// Discards the sidecar file and adds every block read by ```reader``` again.
+    pub fn rebuild(&mut self, reader: &mut Reader) -> Result<u64> {
+        self.file.set_len(0)?;
+        self.file.write_all(&self.header())?;
+        self.filters.clear();
+        self.last.clear();
+        self.last_hash = None;
+        self.count = 0;
+        self.update(reader)
+    }
+
+    /// Returns the indexes of the blocks whose key is ```key```, after adding any blocks that have
+    /// been appended since the index was last updated. Only the groups whose filter may contain
+    /// ```key``` are read, so a key that does not exist usually requires no reads at all.
+    pub fn find(&mut self, reader: &mut Reader, key: &[u8]) -> Result<Vec<u64>> {
+        self.update(reader)?;
+        let groups: Vec<u64> = self.groups(key).collect();
+        let mut found: Vec<u64> = Vec::new();
+        let mut data: Vec<u8> = vec![0; reader.data_size()];
+        for group in groups {
+            let start: u64 = group * self.group_size;
+            let end: u64 = self.count.min(start + self.group_size);
+            reader.seek(start)?;
+            for index in start..end {
+                reader.read_data(&mut data)?;
+                if (self.extract)(&data)? == key {
+                    found.push(index);
+                }
+            }
+        }
+        Ok(found)
+    }
+}
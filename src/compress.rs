@@ -0,0 +1,133 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+use crate::io::{Error, Result};
+
+/// The algorithm used to compress the data section of each block. The algorithm and its
+/// level are recorded in the file header, so readers need no configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Compression {
+    #[default]
+    None,
+    /// LZ4 block compression. Requires the ```lz4``` feature.
+    Lz4,
+    /// Zstandard compression at the given level. Requires the ```zstd``` feature.
+    Zstd(i8),
+}
+
+impl Compression {
+    /// Transmutates the algorithm and level into the two bytes stored in the file header.
+    pub(crate) fn to_bytes(self) -> [u8; 2] {
+        match self {
+            Compression::None => [0, 0],
+            Compression::Lz4 => [1, 0],
+            Compression::Zstd(level) => [2, level as u8],
+        }
+    }
+
+    /// Transmutates the two bytes stored in the file header into an algorithm and level.
+    pub(crate) fn from_bytes(buf: [u8; 2]) -> Result<Self> {
+        match buf {
+            [0, _] => Ok(Compression::None),
+            [1, _] => Ok(Compression::Lz4),
+            [2, level] => Ok(Compression::Zstd(level as i8)),
+            [codec, _] => Err(Error::UnsupportedCompression(codec)),
+        }
+    }
+
+    /// Returns the largest possible size of ```size``` bytes of data after compression.
+    pub(crate) fn max_compressed_size(self, size: usize) -> Result<usize> {
+        match self {
+            Compression::None => Ok(size),
+            #[cfg(feature = "lz4")]
+            Compression::Lz4 => Ok(lz4_flex::block::get_maximum_output_size(size)),
+            #[cfg(feature = "zstd")]
+            Compression::Zstd(_) => Ok(zstd::zstd_safe::compress_bound(size)),
+            #[allow(unreachable_patterns)]
+            _ => Err(self.not_enabled()),
+        }
+    }
+
+    /// Compresses ```src``` into ```dst``` and returns the compressed size, or
+    /// Err(Error::CompressedDataTooLarge) if the compressed data does not fit.
+    pub(crate) fn compress(self, src: &[u8], dst: &mut [u8]) -> Result<usize> {
+        match self {
+            Compression::None => {
+                if src.len() > dst.len() {
+                    return Err(Error::CompressedDataTooLarge);
+                }
+                dst[0..src.len()].copy_from_slice(src);
+                Ok(src.len())
+            }
+            #[cfg(feature = "lz4")]
+            Compression::Lz4 => {
+                // lz4_flex requires room for the worst case, so compress into a scratch buffer
+                let compressed: Vec<u8> = lz4_flex::block::compress(src);
+                if compressed.len() > dst.len() {
+                    return Err(Error::CompressedDataTooLarge);
+                }
+                dst[0..compressed.len()].copy_from_slice(&compressed);
+                Ok(compressed.len())
+            }
+            #[cfg(feature = "zstd")]
+            Compression::Zstd(level) => zstd::bulk::compress_to_buffer(src, dst, level as i32)
+                .map_err(|_| Error::CompressedDataTooLarge),
+            #[allow(unreachable_patterns)]
+            _ => Err(self.not_enabled()),
+        }
+    }
+
+    /// Decompresses ```src``` into ```dst```, whose length must be exactly equal to the size
+    /// of the data before it was compressed. Returns Err(Error::InvalidCompressedData) if not.
+    pub(crate) fn decompress(self, src: &[u8], dst: &mut [u8]) -> Result<()> {
+        let size: usize = match self {
+            Compression::None => {
+                if src.len() == dst.len() {
+                    dst.copy_from_slice(src);
+                }
+                src.len()
+            }
+            #[cfg(feature = "lz4")]
+            Compression::Lz4 => lz4_flex::block::decompress_into(src, dst)
+                .map_err(|_| Error::InvalidCompressedData)?,
+            #[cfg(feature = "zstd")]
+            Compression::Zstd(_) => zstd::bulk::decompress_to_buffer(src, dst)
+                .map_err(|_| Error::InvalidCompressedData)?,
+            #[allow(unreachable_patterns)]
+            _ => return Err(self.not_enabled()),
+        };
+        if size != dst.len() {
+            Err(Error::InvalidCompressedData)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns the error for an algorithm whose feature is not enabled.
+    #[allow(dead_code)]
+    fn not_enabled(self) -> Error {
+        match self {
+            Compression::Zstd(_) => Error::FeatureNotEnabled("zstd"),
+            _ => Error::FeatureNotEnabled("lz4"),
+        }
+    }
+}
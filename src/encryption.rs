@@ -0,0 +1,79 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+#[cfg(feature = "encryption")]
+use chacha20poly1305::aead::{AeadCore, AeadInPlace, KeyInit, OsRng};
+#[cfg(feature = "encryption")]
+use chacha20poly1305::{Tag, XChaCha20Poly1305, XNonce};
+
+#[cfg(feature = "encryption")]
+pub use chacha20poly1305::Key as EncryptionKey;
+
+/// The size of the random nonce stored at the start of each encrypted data section.
+pub const NONCE_SIZE: usize = 24;
+
+/// The size of the authentication tag stored at the end of each encrypted data section.
+pub const TAG_SIZE: usize = 16;
+
+/// Returns a new random key from the operating system's random number generator.
+#[cfg(feature = "encryption")]
+pub fn generate_key() -> EncryptionKey {
+    XChaCha20Poly1305::generate_key(&mut OsRng)
+}
+
+/// Encrypts ```buf``` in place with XChaCha20-Poly1305 under ```key```, authenticating ```aad```
+/// along with it. A random nonce is copied into ```nonce``` and the tag into ```tag```.
+#[cfg(feature = "encryption")]
+pub(crate) fn seal(
+    key: &EncryptionKey,
+    aad: &[u8],
+    nonce: &mut [u8],
+    buf: &mut [u8],
+    tag: &mut [u8],
+) -> bool {
+    let cipher: XChaCha20Poly1305 = XChaCha20Poly1305::new(key);
+    let random: XNonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    match cipher.encrypt_in_place_detached(&random, aad, buf) {
+        Ok(t) => {
+            nonce.copy_from_slice(&random);
+            tag.copy_from_slice(&t);
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// Decrypts ```buf``` in place. Returns true if ```tag``` authenticates both ```buf``` and ```aad```
+/// under ```key``` and ```nonce```, or false if either was modified or the key is wrong.
+#[cfg(feature = "encryption")]
+pub(crate) fn open(
+    key: &EncryptionKey,
+    aad: &[u8],
+    nonce: &[u8],
+    buf: &mut [u8],
+    tag: &[u8],
+) -> bool {
+    let cipher: XChaCha20Poly1305 = XChaCha20Poly1305::new(key);
+    cipher
+        .decrypt_in_place_detached(XNonce::from_slice(nonce), aad, buf, Tag::from_slice(tag))
+        .is_ok()
+}
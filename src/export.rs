@@ -0,0 +1,270 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+use crate::io::{Deserialize, Reader, Result};
+use crate::merkle::MerkleTree;
+use bc_hash::sha256::{Digest, DIGEST_SIZE};
+use serde_json::Map;
+use std::io::Write;
+
+pub use serde_json::Value;
+
+const HEX_CHARS: &[u8; 16] = b"0123456789abcdef";
+const BASE64_CHARS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// The text encoding used for the data section of each exported block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Hex,
+    Base64,
+}
+
+impl Encoding {
+    /// Encodes ```bytes``` as a lowercase hex or padded base64 string.
+    pub fn encode(&self, bytes: &[u8]) -> String {
+        match self {
+            Encoding::Hex => {
+                let mut s: String = String::with_capacity(bytes.len() * 2);
+                for b in bytes {
+                    s.push(HEX_CHARS[(b >> 4) as usize] as char);
+                    s.push(HEX_CHARS[(b & 0x0f) as usize] as char);
+                }
+                s
+            }
+            Encoding::Base64 => {
+                let mut s: String = String::with_capacity(bytes.len().div_ceil(3) * 4);
+                for chunk in bytes.chunks(3) {
+                    let n: u32 = chunk
+                        .iter()
+                        .enumerate()
+                        .fold(0, |n, (i, b)| n | (*b as u32) << (16 - 8 * i));
+                    for i in 0..4 {
+                        if i <= chunk.len() {
+                            s.push(BASE64_CHARS[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+                        } else {
+                            s.push('=');
+                        }
+                    }
+                }
+                s
+            }
+        }
+    }
+
+    /// Decodes a string produced by ```encode()```. Returns None if ```s``` is not valid.
+    pub fn decode(&self, s: &str) -> Option<Vec<u8>> {
+        let s: &[u8] = s.as_bytes();
+        match self {
+            Encoding::Hex => {
+                if !s.len().is_multiple_of(2) {
+                    return None;
+                }
+                let nibble = |c: u8| (c as char).to_digit(16).map(|d| d as u8);
+                s.chunks(2)
+                    .map(|pair| Some(nibble(pair[0])? << 4 | nibble(pair[1])?))
+                    .collect()
+            }
+            Encoding::Base64 => {
+                if !s.len().is_multiple_of(4) {
+                    return None;
+                }
+                let mut bytes: Vec<u8> = Vec::with_capacity(s.len() / 4 * 3);
+                for (i, chunk) in s.chunks(4).enumerate() {
+                    let padding: usize = chunk.iter().rev().take_while(|c| **c == b'=').count();
+                    if padding > 2 || (padding > 0 && (i + 1) * 4 != s.len()) {
+                        return None;
+                    }
+                    let mut n: u32 = 0;
+                    for c in &chunk[0..4 - padding] {
+                        let d: usize = BASE64_CHARS.iter().position(|x| x == c)?;
+                        n = n << 6 | d as u32;
+                    }
+                    n <<= 6 * padding;
+                    bytes.extend_from_slice(&n.to_be_bytes()[1..4 - padding]);
+                }
+                Some(bytes)
+            }
+        }
+    }
+}
+
+/// Implemented by record types that want to contribute decoded fields to each exported block.
+pub trait Export: Deserialize {
+    /// Returns the (name, value) pairs to include in the exported record of this block.
+    fn fields(&self) -> Vec<(&'static str, Value)>;
+}
+
+/// Placeholder record used when exporting blocks without any decoded fields.
+struct NoFields;
+
+impl Deserialize for NoFields {
+    fn deserialize(_buf: &[u8]) -> Result<Self> {
+        Ok(NoFields)
+    }
+}
+
+impl Export for NoFields {
+    fn fields(&self) -> Vec<(&'static str, Value)> {
+        Vec::new()
+    }
+}
+
+/// Returns the hex encoding of a digest.
+fn digest_to_hex(digest: &Digest) -> Result<String> {
+    let mut bytes: [u8; DIGEST_SIZE] = [0; DIGEST_SIZE];
+    digest.serialize(&mut bytes)?;
+    Ok(Encoding::Hex.encode(&bytes))
+}
+
+/// Reads every block from the start of the stream and passes its decoded record to ```f```.
+/// Each record contains the block's index, previous block hash, own hash, and data section,
+/// followed by any fields contributed by ```T```. Returns the number of blocks visited and
+/// the Merkle root over all of them.
+fn for_each_record<T, F>(reader: &mut Reader, encoding: Encoding, mut f: F) -> Result<(u64, Digest)>
+where
+    T: Export,
+    F: FnMut(Vec<(&'static str, Value)>) -> Result<()>,
+{
+    let mut tree: MerkleTree = MerkleTree::new();
+    let block_count: u64 = reader.block_count()?;
+    let mut data: Vec<u8> = vec![0; reader.data_size()];
+    let mut buf: Vec<u8> = vec![0; reader.block_size()];
+    reader.rewind()?;
+    for index in 0..block_count {
+        reader.read_block(&mut buf)?;
+        reader.decode_data(&buf, &mut data)?;
+        let hash: Digest = reader.block_hash(&buf)?;
+        let mut record: Vec<(&'static str, Value)> = vec![
+            ("index", Value::from(index)),
+            (
+                "prev_hash",
+                Value::from(Encoding::Hex.encode(&buf[0..DIGEST_SIZE])),
+            ),
+            ("hash", Value::from(digest_to_hex(&hash)?)),
+            ("data", Value::from(encoding.encode(&data))),
+        ];
+        record.extend(T::deserialize(&data)?.fields());
+        f(record)?;
+        tree.push(&hash)?;
+    }
+    Ok((block_count, tree.root()?))
+}
+
+/// Writes every block in the stream to ```out``` as one JSON object per line. Returns the number
+/// of blocks written.
+pub fn to_jsonl<W: Write>(reader: &mut Reader, out: &mut W, encoding: Encoding) -> Result<u64> {
+    write_jsonl::<NoFields, W>(reader, out, encoding, false)
+}
+
+/// Same as ```to_jsonl()```, but also decodes the data section of each block as a ```T```
+/// and adds the fields it returns to each JSON object.
+pub fn to_jsonl_with<T: Export, W: Write>(
+    reader: &mut Reader,
+    out: &mut W,
+    encoding: Encoding,
+) -> Result<u64> {
+    write_jsonl::<T, W>(reader, out, encoding, false)
+}
+
+/// Same as ```to_jsonl()```, but follows the blocks with a trailer object holding the
+/// ```block_count``` and the ```merkle_root``` over every block hash, so that the whole chain can
+/// be verified when it is imported. The trailer has none of the fields of a block, so this output
+/// is meant for ```import::from_jsonl()``` rather than for loading elsewhere.
+pub fn to_jsonl_with_trailer<W: Write>(
+    reader: &mut Reader,
+    out: &mut W,
+    encoding: Encoding,
+) -> Result<u64> {
+    write_jsonl::<NoFields, W>(reader, out, encoding, true)
+}
+
+/// Writes every block in the stream to ```out``` as one JSON object per line, with the fields
+/// contributed by ```T```, followed by the Merkle root trailer if ```trailer``` is true.
+fn write_jsonl<T: Export, W: Write>(
+    reader: &mut Reader,
+    out: &mut W,
+    encoding: Encoding,
+    trailer: bool,
+) -> Result<u64> {
+    let (count, root) = for_each_record::<T, _>(reader, encoding, |record| {
+        let mut obj: Map<String, Value> = Map::new();
+        for (name, value) in record {
+            obj.entry(name).or_insert(value);
+        }
+        writeln!(out, "{}", Value::Object(obj))?;
+        Ok(())
+    })?;
+    if trailer {
+        let mut obj: Map<String, Value> = Map::new();
+        obj.insert(String::from("block_count"), Value::from(count));
+        obj.insert(
+            String::from("merkle_root"),
+            Value::from(digest_to_hex(&root)?),
+        );
+        writeln!(out, "{}", Value::Object(obj))?;
+    }
+    out.flush()?;
+    Ok(count)
+}
+
+/// Quotes a CSV field if it contains a delimiter, quote, or line break.
+fn csv_field(value: &Value) -> String {
+    let s: String = match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        v => v.to_string(),
+    };
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s
+    }
+}
+
+/// Writes every block in the stream to ```out``` as CSV, preceded by a header row. Returns the
+/// number of blocks written.
+pub fn to_csv<W: Write>(reader: &mut Reader, out: &mut W, encoding: Encoding) -> Result<u64> {
+    to_csv_with::<NoFields, W>(reader, out, encoding)
+}
+
+/// Same as ```to_csv()```, but also decodes the data section of each block as a ```T```
+/// and appends the fields it returns as extra columns. The header row is taken from
+/// the fields of the first block.
+pub fn to_csv_with<T: Export, W: Write>(
+    reader: &mut Reader,
+    out: &mut W,
+    encoding: Encoding,
+) -> Result<u64> {
+    let mut header: bool = false;
+    let (count, _) = for_each_record::<T, _>(reader, encoding, |record| {
+        if !header {
+            let names: Vec<&str> = record.iter().map(|(name, _)| *name).collect();
+            writeln!(out, "{}", names.join(","))?;
+            header = true;
+        }
+        let row: Vec<String> = record.iter().map(|(_, value)| csv_field(value)).collect();
+        writeln!(out, "{}", row.join(","))?;
+        Ok(())
+    })?;
+    out.flush()?;
+    Ok(count)
+}
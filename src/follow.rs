@@ -0,0 +1,167 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+use crate::io::{Reader, Result};
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+/// The default maximum time to wait between checks for new blocks.
+const DEFAULT_INTERVAL: Duration = Duration::from_millis(250);
+
+/// A blocking iterator over the blocks of a stream that waits for new blocks to be appended
+/// once it reaches the end of the stream. Each item is the index of a block together with
+/// the entire block. A partially written block at the end of the stream is not returned until
+/// it is complete. On Linux the underlying file is watched with inotify, otherwise (or if
+/// inotify is unavailable) the file size is polled.
+#[derive(Debug)]
+pub struct Follow<'r, 'a> {
+    reader: &'r mut Reader<'a>,
+    index: u64,
+    interval: Duration,
+    watcher: Watcher,
+}
+
+impl<'r, 'a> Follow<'r, 'a> {
+    pub(crate) fn new(reader: &'r mut Reader<'a>, index: u64) -> Self {
+        let watcher: Watcher = Watcher::new(reader.path());
+        Self {
+            reader,
+            index,
+            interval: DEFAULT_INTERVAL,
+            watcher,
+        }
+    }
+
+    /// Sets the maximum time to wait between checks for new blocks. When polling, this is the
+    /// polling interval. When watching with inotify, this bounds the wait in case an event is missed.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Returns the reader that blocks are read from.
+    #[inline]
+    pub fn reader(&self) -> &Reader<'a> {
+        self.reader
+    }
+
+    /// Returns the index of the next block to be returned.
+    #[inline]
+    pub fn index(&self) -> u64 {
+        self.index
+    }
+
+    /// Returns the number of complete blocks in the stream, ignoring a partially written last block.
+    fn complete_blocks(&self) -> Result<u64> {
+        Ok(self.reader.stream_size()? / self.reader.block_size() as u64)
+    }
+}
+
+impl Iterator for Follow<'_, '_> {
+    type Item = Result<(u64, Vec<u8>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.complete_blocks() {
+                Ok(count) if count > self.index => break,
+                Ok(_) => self.watcher.wait(self.interval),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        let index: u64 = self.index;
+        let mut buf: Vec<u8> = vec![0; self.reader.block_size()];
+        Some(self.reader.read_block_at(index, &mut buf).map(|_| {
+            self.index += 1;
+            (index, buf)
+        }))
+    }
+}
+
+/// Waits for the underlying blockchain file to change.
+#[derive(Debug)]
+enum Watcher {
+    #[cfg(target_os = "linux")]
+    Inotify(std::os::fd::OwnedFd),
+    Poll,
+}
+
+impl Watcher {
+    /// Watches ```path``` with inotify, falling back to polling if that fails.
+    #[cfg(target_os = "linux")]
+    fn new(path: &Path) -> Self {
+        use std::ffi::CString;
+        use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+        use std::os::unix::ffi::OsStrExt;
+
+        let path: CString = match CString::new(path.as_os_str().as_bytes()) {
+            Ok(path) => path,
+            Err(_) => return Watcher::Poll,
+        };
+        // SAFETY: the descriptor is owned by the returned OwnedFd and path is a valid C string.
+        unsafe {
+            let fd: i32 = libc::inotify_init1(libc::IN_NONBLOCK | libc::IN_CLOEXEC);
+            if fd < 0 {
+                return Watcher::Poll;
+            }
+            let fd: OwnedFd = OwnedFd::from_raw_fd(fd);
+            let mask: u32 = libc::IN_MODIFY | libc::IN_CLOSE_WRITE | libc::IN_ATTRIB;
+            if libc::inotify_add_watch(fd.as_raw_fd(), path.as_ptr(), mask) < 0 {
+                Watcher::Poll
+            } else {
+                Watcher::Inotify(fd)
+            }
+        }
+    }
+
+    /// Polls ```path``` for changes.
+    #[cfg(not(target_os = "linux"))]
+    fn new(_path: &Path) -> Self {
+        Watcher::Poll
+    }
+
+    /// Blocks until the file changes or ```timeout``` elapses.
+    fn wait(&mut self, timeout: Duration) {
+        match self {
+            #[cfg(target_os = "linux")]
+            Watcher::Inotify(fd) => {
+                use std::os::fd::AsRawFd;
+
+                let mut pollfd: libc::pollfd = libc::pollfd {
+                    fd: fd.as_raw_fd(),
+                    events: libc::POLLIN,
+                    revents: 0,
+                };
+                let millis: i32 = timeout.as_millis().min(i32::MAX as u128) as i32;
+                let mut buf: [u8; 4096] = [0; 4096];
+                // SAFETY: pollfd and buf are valid for the duration of each call.
+                unsafe {
+                    if libc::poll(&mut pollfd, 1, millis) > 0 {
+                        // drain the pending events, only their arrival matters
+                        while libc::read(pollfd.fd, buf.as_mut_ptr().cast(), buf.len()) > 0 {}
+                    }
+                }
+            }
+            Watcher::Poll => thread::sleep(timeout),
+        }
+    }
+}
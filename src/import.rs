@@ -0,0 +1,142 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+use crate::export::Encoding;
+use crate::io::{Error, File, Result, Serialize, Writer};
+use crate::merkle::MerkleTree;
+use bc_hash::sha256::Digest;
+use serde_json::Value;
+use std::io::BufRead;
+use std::path::Path;
+
+/// The raw data section of an imported block.
+pub(crate) struct RawData<'a>(pub(crate) &'a [u8]);
+
+impl Serialize for RawData<'_> {
+    fn serialize(&self, buf: &mut [u8]) -> Result<()> {
+        if buf.len() != self.0.len() {
+            Err(Error::InvalidSliceLength)
+        } else {
+            buf.copy_from_slice(self.0);
+            Ok(())
+        }
+    }
+}
+
+/// Returns the string field ```name``` of a JSON record decoded with ```encoding```.
+fn decode_field(record: &Value, name: &str, encoding: Encoding, line: u64) -> Result<Vec<u8>> {
+    record
+        .get(name)
+        .and_then(Value::as_str)
+        .and_then(|s| encoding.decode(s))
+        .ok_or(Error::InvalidRecord(line))
+}
+
+/// Creates a new blockchain file at ```path``` from JSON lines in the format written by
+/// ```export::to_jsonl()```, one block per line, where ```encoding``` is the encoding of
+/// the ```data``` field. The first record becomes the genisis block and every other block
+/// is linked to its predecessor by recomputing its previous block hash. If ```verify``` is
+/// true, the ```prev_hash``` field of each record after the first must match the recomputed
+/// hash or Err(Error::InvalidBlockHash(index)) is returned, and if the input ends with the
+/// trailer written by ```export::to_jsonl_with_trailer()```, the block count and Merkle root of
+/// the new chain must match it or Err(Error::InvalidMerkleRoot) is returned. Blank lines are
+/// ignored. If any record is rejected, the partially written file at ```path``` is removed.
+pub fn from_jsonl<R: BufRead>(
+    input: R,
+    path: &Path,
+    encoding: Encoding,
+    verify: bool,
+) -> Result<File> {
+    let mut records = input
+        .lines()
+        .enumerate()
+        .map(|(n, line)| (n as u64 + 1, line))
+        .filter(|(_, line)| !matches!(line, Ok(s) if s.trim().is_empty()))
+        .map(|(n, line)| -> Result<(u64, Value)> {
+            let value: Value = serde_json::from_str(&line?).map_err(|_| Error::InvalidRecord(n))?;
+            Ok((n, value))
+        });
+
+    let (line, genisis) = records.next().ok_or(Error::FileIsEmpty)??;
+    let data: Vec<u8> = decode_field(&genisis, "data", encoding, line)?;
+    let mut file: File = File::create_new(path, &mut RawData(&data), data.len())?;
+    match append_records(&mut file, records, encoding, verify) {
+        Ok(()) => Ok(file),
+        Err(e) => {
+            // leave nothing behind, so that the import can be retried with corrected input
+            drop(file);
+            std::fs::remove_file(path)?;
+            Err(e)
+        }
+    }
+}
+
+/// Appends a block to ```file``` for each of the ```records``` after the genisis block. See
+/// ```from_jsonl()``` for the meaning of ```encoding``` and ```verify```.
+fn append_records<I>(file: &mut File, records: I, encoding: Encoding, verify: bool) -> Result<()>
+where
+    I: Iterator<Item = Result<(u64, Value)>>,
+{
+    let mut writer: Writer = Writer::new(file)?;
+    let mut tree: MerkleTree = MerkleTree::new();
+    tree.push(writer.last_hash())?;
+    let mut trailer: bool = false;
+    for (index, record) in records.enumerate() {
+        let (line, record) = record?;
+        if trailer {
+            return Err(Error::InvalidRecord(line));
+        } else if record.get("merkle_root").is_some() {
+            if verify {
+                verify_trailer(&record, &tree, line)?;
+            }
+            trailer = true;
+            continue;
+        }
+        let mut data: Vec<u8> = decode_field(&record, "data", encoding, line)?;
+        if verify {
+            let prev_hash: Vec<u8> = decode_field(&record, "prev_hash", Encoding::Hex, line)?;
+            let prev_hash: Digest =
+                Digest::deserialize(&prev_hash).map_err(|_| Error::InvalidRecord(line))?;
+            if prev_hash != *writer.last_hash() {
+                return Err(Error::InvalidBlockHash(index as u64 + 1));
+            }
+        }
+        writer.append(&mut data)?;
+        tree.push(writer.last_hash())?;
+    }
+    Ok(())
+}
+
+/// Verifies that ```tree``` has the block count and Merkle root in the trailer ```record```.
+fn verify_trailer(record: &Value, tree: &MerkleTree, line: u64) -> Result<()> {
+    let count: u64 = record
+        .get("block_count")
+        .and_then(Value::as_u64)
+        .ok_or(Error::InvalidRecord(line))?;
+    let root: Vec<u8> = decode_field(record, "merkle_root", Encoding::Hex, line)?;
+    let root: Digest = Digest::deserialize(&root).map_err(|_| Error::InvalidRecord(line))?;
+    if count != tree.len() || root != tree.root()? {
+        Err(Error::InvalidMerkleRoot)
+    } else {
+        Ok(())
+    }
+}
@@ -0,0 +1,212 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+use crate::io::{Error, Reader, Result, Writer};
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+/// The size of the block index and key length that precede each key in the sidecar file.
+const ENTRY_HEADER_SIZE: usize = 8 + 4;
+
+/// A function that extracts the key of a block from its data section.
+type KeyFn = Box<dyn Fn(&[u8]) -> Result<Vec<u8>>>;
+
+/// A secondary index that maps a key extracted from the data section of each block to the
+/// indexes of the blocks with that key. The index is kept in a sidecar file next to the chain,
+/// to which one entry is appended per block, and can be rebuilt from the chain at any time.
+pub struct KeyIndex {
+    path: PathBuf,
+    file: fs::File,
+    extract: KeyFn,
+    keys: BTreeMap<Vec<u8>, Vec<u64>>,
+    count: u64,
+}
+
+impl std::fmt::Debug for KeyIndex {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.debug_struct("KeyIndex")
+            .field("path", &self.path)
+            .field("count", &self.count)
+            .field("keys", &self.keys.len())
+            .finish()
+    }
+}
+
+impl KeyIndex {
+    /// Returns the path of the sidecar file of the index called ```name``` of the chain at ```chain```.
+    pub fn path(chain: &Path, name: &str) -> PathBuf {
+        let mut path: OsString = chain.as_os_str().to_os_string();
+        path.push(format!(".{}.idx", name));
+        PathBuf::from(path)
+    }
+
+    /// Opens the index called ```name``` of the chain read by ```reader```, creating its sidecar
+    /// file if it does not exist, and indexes any blocks appended since it was last updated,
+    /// including blocks appended directly with ```Writer::append()```. ```extract``` must return
+    /// the same key for a block every time the index is opened. If the sidecar file does not match
+    /// the chain, such as when it has more blocks than the chain or the key of its last block
+    /// differs, the index is rebuilt.
+    pub fn open<F>(reader: &mut Reader, name: &str, extract: F) -> Result<KeyIndex>
+    where
+        F: Fn(&[u8]) -> Result<Vec<u8>> + 'static,
+    {
+        let path: PathBuf = Self::path(reader.path(), name);
+        let file: fs::File = fs::File::options()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(&path)?;
+        let mut index: KeyIndex = Self {
+            path,
+            file,
+            extract: Box::new(extract),
+            keys: BTreeMap::new(),
+            count: 0,
+        };
+        if !index.load()? || index.count > reader.block_count()? || !index.matches_last(reader)? {
+            index.rebuild(reader)?;
+        } else {
+            index.update(reader)?;
+        }
+        Ok(index)
+    }
+
+    /// Loads the entries of the sidecar file. A partially written last entry is discarded. Returns
+    /// false if the entries are not for consecutive blocks starting at 0.
+    fn load(&mut self) -> Result<bool> {
+        let mut buf: Vec<u8> = Vec::new();
+        (&self.file).read_to_end(&mut buf)?;
+        let mut pos: usize = 0;
+        while pos + ENTRY_HEADER_SIZE <= buf.len() {
+            let index: u64 = u64::from_le_bytes(buf[pos..pos + 8].try_into().unwrap());
+            let length: usize =
+                u32::from_le_bytes(buf[pos + 8..pos + ENTRY_HEADER_SIZE].try_into().unwrap())
+                    as usize;
+            let start: usize = pos + ENTRY_HEADER_SIZE;
+            if start + length > buf.len() {
+                break;
+            } else if index != self.count {
+                return Ok(false);
+            }
+            self.keys
+                .entry(buf[start..start + length].to_vec())
+                .or_default()
+                .push(index);
+            self.count += 1;
+            pos = start + length;
+        }
+        if pos != buf.len() {
+            self.file.set_len(pos as u64)?;
+        }
+        Ok(true)
+    }
+
+    /// Returns true if the key of the last indexed block is still the key of that block in the
+    /// chain read by ```reader```.
+    fn matches_last(&self, reader: &mut Reader) -> Result<bool> {
+        if self.count == 0 {
+            return Ok(true);
+        }
+        let index: u64 = self.count - 1;
+        let mut data: Vec<u8> = vec![0; reader.data_size()];
+        reader.read_data_at(index, &mut data)?;
+        let key: Vec<u8> = (self.extract)(&data)?;
+        Ok(self.get(&key).last() == Some(&index))
+    }
+
+    /// Returns the number of blocks that have been indexed.
+    #[inline]
+    pub fn len(&self) -> u64 {
+        self.count
+    }
+
+    /// Returns true if no blocks have been indexed.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Returns the indexes of the blocks whose key is ```key```, in ascending order. Blocks appended
+    /// without the index are not included until ```update()``` is called, which
+    /// ```Reader::find_by_key()``` does first.
+    pub fn get(&self, key: &[u8]) -> &[u64] {
+        self.keys.get(key).map_or(&[], |indexes| &indexes[..])
+    }
+
+    /// Adds the block located at ```index```, whose key is ```key```, to the index.
+    /// Blocks must be added in order, so ```index``` must be exactly equal to ```len()```.
+    fn insert(&mut self, index: u64, key: Vec<u8>) -> Result<()> {
+        let mut entry: Vec<u8> = Vec::with_capacity(ENTRY_HEADER_SIZE + key.len());
+        entry.extend_from_slice(&index.to_le_bytes());
+        entry.extend_from_slice(&(key.len() as u32).to_le_bytes());
+        entry.extend_from_slice(&key);
+        self.file.write_all(&entry)?;
+        self.keys.entry(key).or_default().push(index);
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Indexes every block read by ```reader``` that has not been indexed yet.
+    /// Returns the number of blocks that were added.
+    pub fn update(&mut self, reader: &mut Reader) -> Result<u64> {
+        let block_count: u64 = reader.block_count()?;
+        let start: u64 = self.count;
+        let mut data: Vec<u8> = vec![0; reader.data_size()];
+        if start < block_count {
+            reader.seek(start)?;
+        }
+        for index in start..block_count {
+            reader.read_data(&mut data)?;
+            let key: Vec<u8> = (self.extract)(&data)?;
+            self.insert(index, key)?;
+        }
+        self.file.flush()?;
+        Ok(block_count - start)
+    }
+
+    /// Discards the sidecar file and indexes every block read by ```reader``` again.
+    pub fn rebuild(&mut self, reader: &mut Reader) -> Result<u64> {
+        self.file.set_len(0)?;
+        self.keys.clear();
+        self.count = 0;
+        self.update(reader)
+    }
+
+    /// Appends a new block containing ```data``` with ```writer``` and adds it to the index. The
+    /// key is extracted before the block is written, so a block is never appended without its key.
+    /// Any blocks appended without the index must be added with ```update()``` first, or
+    /// Err(Error::IndexStale) is returned.
+    pub fn append(&mut self, writer: &mut Writer, data: &mut [u8]) -> Result<()> {
+        let index: u64 = writer.block_count()?;
+        if index != self.count {
+            return Err(Error::IndexStale);
+        }
+        let key: Vec<u8> = (self.extract)(data)?;
+        writer.append(data)?;
+        self.insert(index, key)?;
+        self.file.flush()?;
+        Ok(())
+    }
+}
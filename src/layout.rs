@@ -0,0 +1,217 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+use crate::io::{Error, Result};
+use bc_hash::sha256::{Digest, DIGEST_SIZE};
+
+/// The size of a fixed layout record, built up one field at a time in a constant expression.
+/// For example, ```Layout::new().i64().u64().digest().size()``` is 48. Each prefix of a layout
+/// gives the offset of the field that follows it, so the fields of a record can be named by
+/// defining the layout one constant at a time, such as ```const AMOUNT: Layout =
+/// Layout::new().i64();``` followed by ```const LAYOUT: Layout = AMOUNT.u64();```, where
+/// ```AMOUNT.offset()``` is the offset of the ```u64``` field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Layout {
+    size: usize,
+}
+
+/// Defines a method that appends an integer field of type ```$t``` to a layout.
+macro_rules! field {
+    ($name:ident, $t:ty) => {
+        #[doc = concat!("Appends a ```", stringify!($t), "``` field to the layout.")]
+        pub const fn $name(self) -> Self {
+            self.bytes(std::mem::size_of::<$t>())
+        }
+    };
+}
+
+impl Layout {
+    /// Creates and returns a new, empty layout.
+    pub const fn new() -> Self {
+        Self { size: 0 }
+    }
+
+    /// Returns the total size of the layout in bytes.
+    pub const fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the offset in bytes of the next field appended to the layout, which is the same
+    /// as its size.
+    pub const fn offset(&self) -> usize {
+        self.size
+    }
+
+    /// Appends a field of ```size``` bytes to the layout.
+    pub const fn bytes(self, size: usize) -> Self {
+        Self {
+            size: self.size + size,
+        }
+    }
+
+    field!(u8, u8);
+    field!(u16, u16);
+    field!(u32, u32);
+    field!(u64, u64);
+    field!(i8, i8);
+    field!(i16, i16);
+    field!(i32, i32);
+    field!(i64, i64);
+
+    /// Appends a ```Digest``` field to the layout.
+    pub const fn digest(self) -> Self {
+        self.bytes(DIGEST_SIZE)
+    }
+}
+
+/// Reads and writes the fields of a fixed layout record in order. Every method checks that the
+/// field lies within the buffer and returns Err(Error::InvalidSliceLength) if not.
+#[derive(Debug)]
+pub struct FieldCursor<B> {
+    buf: B,
+    pos: usize,
+}
+
+/// Defines a ```get_``` method that reads an integer in little endian byte order.
+macro_rules! get_le {
+    ($name:ident, $t:ty) => {
+        #[doc = concat!("Reads the next field as a ```", stringify!($t), "``` in little endian byte order.")]
+        pub fn $name(&mut self) -> Result<$t> {
+            Ok(<$t>::from_le_bytes(self.get_bytes()?))
+        }
+    };
+}
+
+/// Defines a ```put_``` method that writes an integer in little endian byte order.
+macro_rules! put_le {
+    ($name:ident, $t:ty) => {
+        #[doc = concat!("Writes ```value``` to the next field as a ```", stringify!($t), "``` in little endian byte order.")]
+        pub fn $name(&mut self, value: $t) -> Result<()> {
+            self.put_bytes(&value.to_le_bytes())
+        }
+    };
+}
+
+impl<B: AsRef<[u8]>> FieldCursor<B> {
+    /// Creates and returns a new cursor at the start of ```buf```.
+    pub fn new(buf: B) -> Self {
+        Self { buf, pos: 0 }
+    }
+
+    /// Creates and returns a new cursor at the start of ```buf```, whose length must be exactly
+    /// equal to the size of ```layout```. If not, then Err(Error::InvalidSliceLength) is returned.
+    pub fn with_layout(buf: B, layout: &Layout) -> Result<Self> {
+        if buf.as_ref().len() != layout.size() {
+            Err(Error::InvalidSliceLength)
+        } else {
+            Ok(Self::new(buf))
+        }
+    }
+
+    /// Returns the offset of the next field in bytes.
+    #[inline]
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Returns the number of bytes after the current position.
+    #[inline]
+    pub fn remaining(&self) -> usize {
+        self.buf.as_ref().len() - self.pos
+    }
+
+    /// Moves the cursor past the next ```size``` bytes.
+    pub fn skip(&mut self, size: usize) -> Result<()> {
+        self.range(size).map(|_| ())
+    }
+
+    /// Returns Ok(()) if every byte of the buffer has been read or written, or
+    /// Err(Error::InvalidSliceLength) if not.
+    pub fn finish(&self) -> Result<()> {
+        if self.remaining() != 0 {
+            Err(Error::InvalidSliceLength)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns the range of the next field of ```size``` bytes and moves the cursor past it.
+    fn range(&mut self, size: usize) -> Result<std::ops::Range<usize>> {
+        match self.pos.checked_add(size) {
+            Some(end) if end <= self.buf.as_ref().len() => {
+                let start: usize = self.pos;
+                self.pos = end;
+                Ok(start..end)
+            }
+            _ => Err(Error::InvalidSliceLength),
+        }
+    }
+
+    /// Returns the next field of ```size``` bytes.
+    pub fn get_slice(&mut self, size: usize) -> Result<&[u8]> {
+        let range: std::ops::Range<usize> = self.range(size)?;
+        Ok(&self.buf.as_ref()[range])
+    }
+
+    /// Returns a copy of the next field of ```N``` bytes.
+    pub fn get_bytes<const N: usize>(&mut self) -> Result<[u8; N]> {
+        Ok(self.get_slice(N)?.try_into().unwrap())
+    }
+
+    /// Reads the next field as a ```Digest```.
+    pub fn get_digest(&mut self) -> Result<Digest> {
+        Ok(Digest::deserialize(self.get_slice(DIGEST_SIZE)?)?)
+    }
+
+    get_le!(get_u8, u8);
+    get_le!(get_u16_le, u16);
+    get_le!(get_u32_le, u32);
+    get_le!(get_u64_le, u64);
+    get_le!(get_i8, i8);
+    get_le!(get_i16_le, i16);
+    get_le!(get_i32_le, i32);
+    get_le!(get_i64_le, i64);
+}
+
+impl<B: AsRef<[u8]> + AsMut<[u8]>> FieldCursor<B> {
+    /// Copies ```bytes``` into the next field.
+    pub fn put_bytes(&mut self, bytes: &[u8]) -> Result<()> {
+        let range: std::ops::Range<usize> = self.range(bytes.len())?;
+        self.buf.as_mut()[range].copy_from_slice(bytes);
+        Ok(())
+    }
+
+    /// Writes ```digest``` to the next field.
+    pub fn put_digest(&mut self, digest: &Digest) -> Result<()> {
+        let range: std::ops::Range<usize> = self.range(DIGEST_SIZE)?;
+        Ok(digest.serialize(&mut self.buf.as_mut()[range])?)
+    }
+
+    put_le!(put_u8, u8);
+    put_le!(put_u16_le, u16);
+    put_le!(put_u32_le, u32);
+    put_le!(put_u64_le, u64);
+    put_le!(put_i8, i8);
+    put_le!(put_i16_le, i16);
+    put_le!(put_i32_le, i32);
+    put_le!(put_i64_le, i64);
+}
@@ -22,10 +22,25 @@
 
 pub mod io {
 
-    use bc_hash::sha256::{Digest, Error as Sha256Error, DIGEST_SIZE};
+    use crate::bloom::BloomIndex;
+    use crate::compress::Compression;
+    use crate::crc::crc32c;
+    #[cfg(feature = "encryption")]
+    use crate::encryption::{self, EncryptionKey};
+    use crate::encryption::{NONCE_SIZE, TAG_SIZE};
+    use crate::follow::Follow;
+    use crate::index::KeyIndex;
+    use crate::reverse::{BlocksRev, DataRev};
+    #[cfg(feature = "signing")]
+    use crate::signing::{self, SigningKey, VerifyingKey};
+    use bc_hash::sha256::Error as Sha256Error;
+    use std::collections::btree_map::{BTreeMap, Entry};
     use std::fmt::{Display, Formatter, Result as FmtResult};
     use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
-    use std::path::Path;
+    #[cfg(unix)]
+    use std::os::unix::fs::FileExt;
+    use std::path::{Path, PathBuf};
+    use std::sync::mpsc::{self, Receiver, Sender};
     use std::{fs, vec};
 
     #[derive(Debug, Clone)]
@@ -41,6 +56,29 @@ pub mod io {
         IntegerOverflow,
         InvalidFileSize,
         InvalidBlockHash(u64),
+        InvalidRecord(u64),
+        InvalidSignature(u64),
+        InvalidChecksum(u64),
+        CompressedDataTooLarge,
+        InvalidCompressedData,
+        UnsupportedCompression(u8),
+        MissingEncryptionKey,
+        InvalidCiphertext,
+        Encryption,
+        BlockPruned(u64),
+        UnknownVersion(u16),
+        RecordTooLarge(usize, usize),
+        InvalidEncoding,
+        DataNotPlain,
+        IndexStale,
+        InvalidMerkleRoot,
+        ChainsDiverged(u64),
+        InvalidMessage,
+        BlockSizeMismatch(usize, usize),
+        MissingSigningKey,
+        NoAuthorizedKeys,
+        UnsupportedFlags(u32),
+        FeatureNotEnabled(&'static str),
         IOError(std::io::ErrorKind),
         Sha256Error(Sha256Error),
     }
@@ -52,6 +90,29 @@ pub mod io {
                 BadStreamPosition(n) => fmt.write_fmt(format_args!("Current stream position {} is not an even multiple of the block size.", n)),
                 BlockNumDoesNotExist => fmt.write_str("Block number too large (out of bounds) and does not exist."),
                 InvalidBlockHash(n) => fmt.write_fmt(format_args!("The previous block hash saved in block number {} is not the same as the previous block's hash", n)),
+                InvalidRecord(n) => fmt.write_fmt(format_args!("The record on line {} is missing a field or could not be decoded.", n)),
+                InvalidSignature(n) => fmt.write_fmt(format_args!("The signature of block number {} was not made by any of the authorized keys.", n)),
+                InvalidChecksum(n) => fmt.write_fmt(format_args!("The checksum of block number {} does not match its contents, so it was corrupted on disk.", n)),
+                CompressedDataTooLarge => fmt.write_str("The compressed data section does not fit in the space reserved for it in the block."),
+                InvalidCompressedData => fmt.write_str("The compressed data section could not be decompressed."),
+                UnsupportedCompression(c) => fmt.write_fmt(format_args!("The file header contains an unsupported compression algorithm {}.", c)),
+                BlockPruned(n) => fmt.write_fmt(format_args!("Block number {} is in a segment that has been pruned.", n)),
+                UnknownVersion(v) => fmt.write_fmt(format_args!("No record layout is registered for schema version {}.", v)),
+                RecordTooLarge(n, c) => fmt.write_fmt(format_args!("The encoded record is {} bytes, which is larger than the {} byte data section.", n, c)),
+                InvalidEncoding => fmt.write_str("The record could not be encoded or decoded."),
+                DataNotPlain => fmt.write_str("The data section is compressed or encrypted, so it can not be viewed in place."),
+                InvalidMerkleRoot => fmt.write_str("The block count or Merkle root of the imported chain does not match the trailer of the export."),
+                IndexStale => fmt.write_str("The index is missing blocks that were appended without it, so it must be updated first."),
+                ChainsDiverged(n) => fmt.write_fmt(format_args!("Block number {} of the replica does not match the primary, so the chains have diverged.", n)),
+                InvalidMessage => fmt.write_str("The replication message is malformed or from an unsupported protocol."),
+                BlockSizeMismatch(p, r) => fmt.write_fmt(format_args!("The primary's block size {} is not the same as the replica's block size {}.", p, r)),
+                MissingEncryptionKey => fmt.write_str("The blockchain is encrypted but no encryption key was given."),
+                InvalidCiphertext => fmt.write_str("The encrypted data section could not be authenticated with the given key."),
+                Encryption => fmt.write_str("The data section could not be encrypted."),
+                MissingSigningKey => fmt.write_str("The blockchain is signed but no signing key was given."),
+                NoAuthorizedKeys => fmt.write_str("The blockchain is signed but no authorized keys were given."),
+                UnsupportedFlags(f) => fmt.write_fmt(format_args!("The file header contains unsupported flags {:#010x}.", f)),
+                FeatureNotEnabled(f) => fmt.write_fmt(format_args!("The blockchain requires the \"{}\" feature, which is not enabled.", f)),
                 InvalidSliceLength => fmt.write_str("Invalide slice length"),
                 ZeroBlockSize => fmt.write_str("Block size can not be zero."),
                 BlockSizeTooBig => fmt.write_str("Block size is greater than u32::MAX - DIGEST_SIZE"),
@@ -96,55 +157,321 @@ pub mod io {
             Self: Sized;
     }
 
-    #[derive(Debug)]
+    /// Derive macros for ```Serialize``` and ```Deserialize``` on structs of integers, byte arrays,
+    /// and ```Digest``` fields with a fixed little endian layout.
+    #[cfg(feature = "derive")]
+    pub use bc_io_derive::{Deserialize, Serialize};
+
+    /// The SHA-256 digest stored in the links of the chain, re-exported so that the derive macros
+    /// can refer to it without the deriving crate depending on ```bc_hash```.
+    pub use bc_hash::sha256::{Digest, DIGEST_SIZE};
+
+    /// Header flag set when every block ends with an Ed25519 signature of the rest of the block.
+    pub const FLAG_SIGNED: u32 = 0x0000_0001;
+
+    /// Header flag set when every block ends with a CRC-32C checksum of the rest of the block.
+    pub const FLAG_CRC32C: u32 = 0x0000_0002;
+
+    /// Header flag set when the hash of each block is calculated over the previous block hash and
+    /// the uncompressed data section, rather than the bytes stored in the file.
+    pub const FLAG_HASH_UNCOMPRESSED: u32 = 0x0000_0004;
+
+    /// Header flag set when the data section of every block is encrypted with XChaCha20-Poly1305.
+    pub const FLAG_ENCRYPTED: u32 = 0x0000_0008;
+
+    /// The flags that are understood by this version of the library.
+    const SUPPORTED_FLAGS: u32 =
+        FLAG_SIGNED | FLAG_CRC32C | FLAG_HASH_UNCOMPRESSED | FLAG_ENCRYPTED;
+
+    /// The size of the length that precedes a compressed data section.
+    const LENGTH_SIZE: usize = 4;
+
+    /// The size of an Ed25519 signature in bytes.
+    pub const SIGNATURE_SIZE: usize = 64;
+
+    /// The size of a CRC-32C checksum in bytes.
+    pub const CHECKSUM_SIZE: usize = 4;
+
+    /// The header of a blockchain file, which is stored in place of the previous block hash
+    /// of the genisis block. Files without any flags have the same layout as files written
+    /// before flags were introduced.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    struct Header {
+        block_size: u32,
+        flags: u32,
+        /// The size of the uncompressed data section, or 0 if the data section is not compressed.
+        data_size: u32,
+        compression: Compression,
+    }
+
+    impl Header {
+        /// Transmutates the header into the first ```DIGEST_SIZE``` bytes of the genisis block.
+        fn write(&self, buf: &mut [u8]) {
+            buf[0..4].copy_from_slice(&self.block_size.to_le_bytes());
+            buf[4..8].copy_from_slice(&self.flags.to_le_bytes());
+            buf[8..12].copy_from_slice(&self.data_size.to_le_bytes());
+            buf[12..14].copy_from_slice(&self.compression.to_bytes());
+        }
+
+        /// Transmutates the first ```DIGEST_SIZE``` bytes of the genisis block into a header.
+        fn read(buf: &[u8]) -> Result<Header> {
+            let header: Header = Header {
+                block_size: u32::from_le_bytes(buf[0..4].try_into().unwrap()),
+                flags: u32::from_le_bytes(buf[4..8].try_into().unwrap()),
+                data_size: u32::from_le_bytes(buf[8..12].try_into().unwrap()),
+                compression: Compression::from_bytes(buf[12..14].try_into().unwrap())?,
+            };
+            let compressed: bool = header.compression != Compression::None;
+            if header.flags & !SUPPORTED_FLAGS != 0 {
+                Err(Error::UnsupportedFlags(header.flags))
+            } else if header.block_size as usize
+                <= DIGEST_SIZE
+                    + header.trailer_size()
+                    + header.encryption_overhead()
+                    + if compressed { LENGTH_SIZE } else { 0 }
+                || (compressed && header.data_size == 0)
+            {
+                Err(Error::ZeroBlockSize)
+            } else {
+                Ok(header)
+            }
+        }
+
+        /// Returns the number of bytes that follow the data section of each block. The signature
+        /// comes first, if any, followed by the checksum, if any.
+        fn trailer_size(&self) -> usize {
+            let mut size: usize = 0;
+            if self.flags & FLAG_SIGNED != 0 {
+                size += SIGNATURE_SIZE;
+            }
+            if self.flags & FLAG_CRC32C != 0 {
+                size += CHECKSUM_SIZE;
+            }
+            size
+        }
+
+        /// Returns the number of bytes added to the data section of each block by encryption.
+        fn encryption_overhead(&self) -> usize {
+            if self.flags & FLAG_ENCRYPTED != 0 {
+                NONCE_SIZE + TAG_SIZE
+            } else {
+                0
+            }
+        }
+    }
+
+    /// Options for creating a new blockchain file with ```File::create_new_with()```.
+    #[derive(Clone, Default)]
+    pub struct Options {
+        /// The algorithm used to compress the data section of each block.
+        pub compression: Compression,
+        /// The number of bytes reserved for each compressed data section, including its 4 byte
+        /// length. Blocks are only smaller than the uncompressed data if this is set, in which case
+        /// appending data that does not compress well enough fails with
+        /// Err(Error::CompressedDataTooLarge). Defaults to the worst case compressed size.
+        pub compressed_size: Option<usize>,
+        /// If true, the hash of each block is calculated over the uncompressed data section, so
+        /// the hash chain does not depend on the compression algorithm. This is ignored if the
+        /// blockchain is encrypted, so that the hash chain can be validated without the key.
+        pub hash_uncompressed: bool,
+        /// If set, the data section of every block is encrypted with this key. The previous
+        /// block hash is left in the clear and authenticated along with the data section.
+        #[cfg(feature = "encryption")]
+        pub encryption_key: Option<EncryptionKey>,
+        /// If true, every block ends with a CRC-32C checksum that is verified on every read.
+        pub crc: bool,
+        /// If set, every block is signed with this key, including the genisis block.
+        #[cfg(feature = "signing")]
+        pub signing_key: Option<SigningKey>,
+    }
+
+    /// Stands in for a secret key when debug formatting, so that the key is never printed.
+    #[cfg(any(feature = "encryption", feature = "signing"))]
+    struct Redacted;
+
+    #[cfg(any(feature = "encryption", feature = "signing"))]
+    impl std::fmt::Debug for Redacted {
+        fn fmt(&self, fmt: &mut Formatter<'_>) -> FmtResult {
+            fmt.write_str("<redacted>")
+        }
+    }
+
+    impl std::fmt::Debug for Options {
+        fn fmt(&self, fmt: &mut Formatter<'_>) -> FmtResult {
+            let mut s: std::fmt::DebugStruct = fmt.debug_struct("Options");
+            s.field("compression", &self.compression)
+                .field("compressed_size", &self.compressed_size)
+                .field("hash_uncompressed", &self.hash_uncompressed);
+            #[cfg(feature = "encryption")]
+            s.field(
+                "encryption_key",
+                &self.encryption_key.as_ref().map(|_| Redacted),
+            );
+            s.field("crc", &self.crc);
+            #[cfg(feature = "signing")]
+            s.field("signing_key", &self.signing_key.as_ref().map(|_| Redacted));
+            s.finish()
+        }
+    }
+
     pub struct File {
         inner: fs::File,
         block_size: usize,
+        path: PathBuf,
+        header: Header,
+        #[cfg(feature = "signing")]
+        signing_key: Option<SigningKey>,
+        #[cfg(feature = "signing")]
+        authorized_keys: Vec<VerifyingKey>,
+        #[cfg(feature = "encryption")]
+        encryption_key: Option<EncryptionKey>,
+    }
+
+    impl std::fmt::Debug for File {
+        fn fmt(&self, fmt: &mut Formatter<'_>) -> FmtResult {
+            let mut s: std::fmt::DebugStruct = fmt.debug_struct("File");
+            s.field("inner", &self.inner)
+                .field("block_size", &self.block_size)
+                .field("path", &self.path)
+                .field("header", &self.header);
+            #[cfg(feature = "signing")]
+            s.field("signing_key", &self.signing_key.as_ref().map(|_| Redacted))
+                .field("authorized_keys", &self.authorized_keys);
+            #[cfg(feature = "encryption")]
+            s.field(
+                "encryption_key",
+                &self.encryption_key.as_ref().map(|_| Redacted),
+            );
+            s.finish()
+        }
     }
 
     impl File {
         /// Creates a new blockchain file in the local file system. 
         pub fn create_new<T: Serialize>(path: &Path, data: &mut T, size: usize) -> Result<File> {
-            if size > (u32::MAX as usize - DIGEST_SIZE) {
+            Self::create_new_with(path, data, size, Options::default())
+        }
+
+        /// Creates a new blockchain file in the local file system using ```options```.
+        /// ```size``` is the size of the data section of each block.
+        pub fn create_new_with<T: Serialize>(
+            path: &Path,
+            data: &mut T,
+            size: usize,
+            options: Options,
+        ) -> Result<File> {
+            let mut flags: u32 = 0;
+            if options.crc {
+                flags |= FLAG_CRC32C;
+            }
+            #[cfg(feature = "signing")]
+            if options.signing_key.is_some() {
+                flags |= FLAG_SIGNED;
+            }
+            #[cfg(feature = "encryption")]
+            if options.encryption_key.is_some() {
+                flags |= FLAG_ENCRYPTED;
+            }
+            let compressed: bool = options.compression != Compression::None;
+            if compressed && options.hash_uncompressed && flags & FLAG_ENCRYPTED == 0 {
+                flags |= FLAG_HASH_UNCOMPRESSED;
+            }
+            let mut header: Header = Header {
+                block_size: 0,
+                flags,
+                data_size: if compressed { size as u32 } else { 0 },
+                compression: options.compression,
+            };
+            let plaintext_size: usize = match options.compressed_size {
+                _ if !compressed => size,
+                Some(compressed_size) => compressed_size,
+                None => LENGTH_SIZE + options.compression.max_compressed_size(size)?,
+            };
+            let overhead: usize =
+                DIGEST_SIZE + header.trailer_size() + header.encryption_overhead();
+            if size > (u32::MAX as usize - overhead)
+                || plaintext_size > (u32::MAX as usize - overhead)
+            {
                 Err(Error::BlockSizeTooBig)
-            } else if size == 0 {
+            } else if size == 0 || (compressed && plaintext_size <= LENGTH_SIZE) {
                 Err(Error::ZeroBlockSize)
             } else {
-                let mut file: fs::File = fs::File::options()
+                let file: fs::File = fs::File::options()
                     .write(true)
                     .read(true)
                     .create_new(true)
                     .open(path)?;
-                let block_size: usize = size + DIGEST_SIZE;
-                let mut buf: Vec<u8> = vec![0; block_size];
-                buf[0..4].copy_from_slice(&(block_size as u32).to_le_bytes());
-                data.serialize(&mut buf[DIGEST_SIZE..block_size])?;
-                file.write_all(&buf)?;
-                file.flush()?;
-                Ok(Self {
+                let block_size: usize = plaintext_size + overhead;
+                header.block_size = block_size as u32;
+                let mut file: File = Self {
                     inner: file,
                     block_size,
-                })
+                    path: path.to_path_buf(),
+                    header,
+                    #[cfg(feature = "signing")]
+                    signing_key: options.signing_key,
+                    #[cfg(feature = "signing")]
+                    authorized_keys: Vec::new(),
+                    #[cfg(feature = "encryption")]
+                    encryption_key: options.encryption_key,
+                };
+                let mut data_buf: Vec<u8> = vec![0; size];
+                data.serialize(&mut data_buf)?;
+                let mut buf: Vec<u8> = vec![0; block_size];
+                header.write(&mut buf[0..DIGEST_SIZE]);
+                file.encode_data(&data_buf, &mut buf)?;
+                file.seal_block(&mut buf)?;
+                file.inner.write_all(&buf)?;
+                file.inner.flush()?;
+                Ok(file)
             }
         }
 
         /// Creates a new BlockChain object from an existing file in the local file system.
         pub fn open_existing(path: &Path) -> Result<File> {
+            Self::open(path, true)
+        }
+
+        /// Same as ```open_existing()```, but does not require the file size to be a multiple of
+        /// the block size, so that a file can be opened while another process is part way through
+        /// appending a block to it, such as to follow it with ```Reader::follow()```. Until that
+        /// block is complete, ```block_count()``` returns Err(Error::InvalidFileSize).
+        pub fn open_growing(path: &Path) -> Result<File> {
+            Self::open(path, false)
+        }
+
+        /// Opens an existing file in the local file system, verifying that its size is a multiple
+        /// of the block size if ```check_size``` is true.
+        fn open(path: &Path, check_size: bool) -> Result<File> {
             if !path.exists() {
                 Err(Error::PathAlreadyExists)
             } else if path.is_dir() {
                 Err(Error::PathIsNotAFile)
             } else {
                 let mut file: fs::File = fs::File::options().write(true).read(true).open(path)?;
+                if file.metadata()?.len() == 0 {
+                    return Err(Error::FileIsEmpty);
+                }
                 file.rewind()?;
-                let mut buffer: [u8; 4] = [0; 4];
+                let mut buffer: [u8; DIGEST_SIZE] = [0; DIGEST_SIZE];
                 file.read_exact(&mut buffer)?;
-                let block_size: usize = u32::from_le_bytes(buffer) as usize;
-                Self::validate_size(&file, block_size)?;
+                let header: Header = Header::read(&buffer)?;
+                let block_size: usize = header.block_size as usize;
+                if check_size {
+                    Self::validate_size(&file, block_size)?;
+                }
                 file.rewind()?;
                 Ok(Self {
                     inner: file,
                     block_size,
+                    path: path.to_path_buf(),
+                    header,
+                    #[cfg(feature = "signing")]
+                    signing_key: None,
+                    #[cfg(feature = "signing")]
+                    authorized_keys: Vec::new(),
+                    #[cfg(feature = "encryption")]
+                    encryption_key: None,
                 })
             }
         }
@@ -155,6 +482,377 @@ pub mod io {
             self.block_size
         }
 
+        /// Returns the size of the data section of each block in bytes. If the blockchain is
+        /// compressed, this is the size of the data section before compression.
+        #[inline]
+        pub fn data_size(&self) -> usize {
+            match self.header.compression {
+                Compression::None => self.plaintext_size(),
+                _ => self.header.data_size as usize,
+            }
+        }
+
+        /// Returns the number of bytes between the previous block hash and the trailer of each
+        /// block, which holds the data section as it is stored in the file.
+        #[inline]
+        fn payload_size(&self) -> usize {
+            self.block_size - DIGEST_SIZE - self.header.trailer_size()
+        }
+
+        /// Returns the algorithm used to compress the data section of each block.
+        #[inline]
+        pub fn compression(&self) -> Compression {
+            self.header.compression
+        }
+
+        /// Returns the location within each block of the data section before it is encrypted,
+        /// which lies between the nonce and the tag if the blockchain is encrypted.
+        #[inline]
+        fn plaintext_range(&self) -> std::ops::Range<usize> {
+            let start: usize = if self.is_encrypted() {
+                DIGEST_SIZE + NONCE_SIZE
+            } else {
+                DIGEST_SIZE
+            };
+            start..start + self.plaintext_size()
+        }
+
+        /// Returns the size of the data section of each block as it is stored, after compression
+        /// but before encryption.
+        #[inline]
+        fn plaintext_size(&self) -> usize {
+            self.payload_size() - self.header.encryption_overhead()
+        }
+
+        /// Returns true if the data section of every block is encrypted.
+        #[inline]
+        pub fn is_encrypted(&self) -> bool {
+            self.header.flags & FLAG_ENCRYPTED != 0
+        }
+
+        /// Transmutates the data section ```data``` into the form it is stored in, within the entire
+        /// block in ```buf```, whose previous block hash must already have been written.
+        fn encode_data(&self, data: &[u8], buf: &mut [u8]) -> Result<()> {
+            let range: std::ops::Range<usize> = self.plaintext_range();
+            let (head, tail) = buf.split_at_mut(range.end);
+            let (head, plaintext) = head.split_at_mut(range.start);
+            match self.header.compression {
+                Compression::None => plaintext.copy_from_slice(data),
+                compression => {
+                    let (length, frame) = plaintext.split_at_mut(LENGTH_SIZE);
+                    let n: usize = compression.compress(data, frame)?;
+                    length.copy_from_slice(&(n as u32).to_le_bytes());
+                    frame[n..].fill(0);
+                }
+            }
+            if self.is_encrypted() {
+                let (prev, nonce) = head.split_at_mut(DIGEST_SIZE);
+                self.encrypt(prev, nonce, plaintext, &mut tail[0..TAG_SIZE])?;
+            }
+            Ok(())
+        }
+
+        /// Transmutates the data section as it is stored within the entire block in ```buf```
+        /// back into ```data```.
+        pub(crate) fn decode_data(&self, buf: &[u8], data: &mut [u8]) -> Result<()> {
+            let range: std::ops::Range<usize> = self.plaintext_range();
+            let mut decrypted: Vec<u8> = Vec::new();
+            let plaintext: &[u8] = if self.is_encrypted() {
+                decrypted.extend_from_slice(&buf[range.clone()]);
+                self.decrypt(
+                    &buf[0..DIGEST_SIZE],
+                    &buf[DIGEST_SIZE..range.start],
+                    &mut decrypted,
+                    &buf[range.end..range.end + TAG_SIZE],
+                )?;
+                &decrypted
+            } else {
+                &buf[range]
+            };
+            match self.header.compression {
+                Compression::None => data.copy_from_slice(plaintext),
+                compression => {
+                    let (length, frame) = plaintext.split_at(LENGTH_SIZE);
+                    let n: usize = u32::from_le_bytes(length.try_into().unwrap()) as usize;
+                    if n > frame.len() {
+                        return Err(Error::InvalidCompressedData);
+                    }
+                    compression.decompress(&frame[0..n], data)?;
+                }
+            }
+            Ok(())
+        }
+
+        /// Encrypts ```plaintext``` in place, authenticating the previous block hash ```prev```
+        /// along with it, and copies the random nonce and tag into ```nonce``` and ```tag```.
+        fn encrypt(
+            &self,
+            prev: &[u8],
+            nonce: &mut [u8],
+            plaintext: &mut [u8],
+            tag: &mut [u8],
+        ) -> Result<()> {
+            #[cfg(feature = "encryption")]
+            {
+                let key: &EncryptionKey = self
+                    .encryption_key
+                    .as_ref()
+                    .ok_or(Error::MissingEncryptionKey)?;
+                if encryption::seal(key, prev, nonce, plaintext, tag) {
+                    Ok(())
+                } else {
+                    Err(Error::Encryption)
+                }
+            }
+            #[cfg(not(feature = "encryption"))]
+            {
+                let _ = (prev, nonce, plaintext, tag);
+                Err(Error::FeatureNotEnabled("encryption"))
+            }
+        }
+
+        /// Decrypts ```ciphertext``` in place. Returns Err(Error::InvalidCiphertext) if it, or the
+        /// previous block hash ```prev```, was modified or the key is wrong.
+        fn decrypt(
+            &self,
+            prev: &[u8],
+            nonce: &[u8],
+            ciphertext: &mut [u8],
+            tag: &[u8],
+        ) -> Result<()> {
+            #[cfg(feature = "encryption")]
+            {
+                let key: &EncryptionKey = self
+                    .encryption_key
+                    .as_ref()
+                    .ok_or(Error::MissingEncryptionKey)?;
+                if encryption::open(key, prev, nonce, ciphertext, tag) {
+                    Ok(())
+                } else {
+                    Err(Error::InvalidCiphertext)
+                }
+            }
+            #[cfg(not(feature = "encryption"))]
+            {
+                let _ = (prev, nonce, ciphertext, tag);
+                Err(Error::FeatureNotEnabled("encryption"))
+            }
+        }
+
+        /// Returns the hash of the entire block in ```buf```, which is linked to by the next block.
+        pub(crate) fn block_hash(&self, buf: &[u8]) -> Result<Digest> {
+            if self.header.flags & FLAG_HASH_UNCOMPRESSED != 0 {
+                let mut block: Vec<u8> = vec![0; DIGEST_SIZE + self.data_size()];
+                block[0..DIGEST_SIZE].copy_from_slice(&buf[0..DIGEST_SIZE]);
+                self.decode_data(buf, &mut block[DIGEST_SIZE..])?;
+                Ok(Digest::from(&block[..]))
+            } else {
+                Ok(Digest::from(buf))
+            }
+        }
+
+        /// Returns the flags stored in the file header.
+        #[inline]
+        pub fn flags(&self) -> u32 {
+            self.header.flags
+        }
+
+        /// Returns the path of the underlying blockchain file.
+        #[inline]
+        pub fn path(&self) -> &Path {
+            &self.path
+        }
+
+        /// Sets the key used to encrypt and decrypt the data sections of an encrypted blockchain.
+        #[cfg(feature = "encryption")]
+        pub fn set_encryption_key(&mut self, key: EncryptionKey) {
+            self.encryption_key = Some(key);
+        }
+
+        /// Sets the key used to sign new blocks appended to a signed blockchain.
+        #[cfg(feature = "signing")]
+        pub fn set_signing_key(&mut self, key: SigningKey) {
+            self.signing_key = Some(key);
+        }
+
+        /// Sets the public keys whose signatures are accepted when validating a signed blockchain.
+        #[cfg(feature = "signing")]
+        pub fn set_authorized_keys(&mut self, keys: Vec<VerifyingKey>) {
+            self.authorized_keys = keys;
+        }
+
+        /// Fills in the trailer of ```buf```, which must contain an entire block whose
+        /// previous block hash and data section have already been written.
+        fn seal_block(&self, buf: &mut [u8]) -> Result<()> {
+            self.sign_block(buf)?;
+            if self.header.flags & FLAG_CRC32C != 0 {
+                let (message, checksum) = buf.split_at_mut(self.block_size - CHECKSUM_SIZE);
+                checksum.copy_from_slice(&crc32c(message).to_le_bytes());
+            }
+            Ok(())
+        }
+
+        /// Builds the entire block in ```buf``` that follows the block whose hash is ```prev_hash```
+        /// and contains the data section ```data```. Returns the hash of the new block.
+        pub(crate) fn build_block(
+            &self,
+            prev_hash: &Digest,
+            data: &[u8],
+            buf: &mut [u8],
+        ) -> Result<Digest> {
+            prev_hash.serialize(&mut buf[0..DIGEST_SIZE])?;
+            self.encode_data(data, buf)?;
+            self.seal_block(buf)?;
+            self.block_hash(buf)
+        }
+
+        /// Signs the previous block hash and data section of the entire block in ```buf```
+        /// and copies the signature into its trailer, if the blockchain is signed.
+        fn sign_block(&self, buf: &mut [u8]) -> Result<()> {
+            if self.header.flags & FLAG_SIGNED != 0 {
+                let (message, trailer) = buf.split_at_mut(DIGEST_SIZE + self.payload_size());
+                #[cfg(feature = "signing")]
+                {
+                    let key: &SigningKey =
+                        self.signing_key.as_ref().ok_or(Error::MissingSigningKey)?;
+                    signing::sign(key, message, &mut trailer[0..SIGNATURE_SIZE]);
+                }
+                #[cfg(not(feature = "signing"))]
+                {
+                    let _ = (message, trailer);
+                    return Err(Error::FeatureNotEnabled("signing"));
+                }
+            }
+            Ok(())
+        }
+
+        /// Verifies the checksum of the entire block in ```buf``` located at ```index```.
+        /// Returns Ok(()) if the blockchain has no checksums or the checksum matches,
+        /// or Err(Error::InvalidChecksum(index)) if not.
+        fn check_checksum(&self, index: u64, buf: &[u8]) -> Result<()> {
+            if self.header.flags & FLAG_CRC32C != 0 {
+                let (message, checksum) = buf.split_at(self.block_size - CHECKSUM_SIZE);
+                if crc32c(message).to_le_bytes() != checksum {
+                    return Err(Error::InvalidChecksum(index));
+                }
+            }
+            Ok(())
+        }
+
+        /// Returns true if the blockchain stores a checksum in every block.
+        #[inline]
+        fn has_checksums(&self) -> bool {
+            self.header.flags & FLAG_CRC32C != 0
+        }
+
+        /// Verifies the signature of the entire block in ```buf``` located at ```index```.
+        /// Returns Ok(()) if the blockchain is not signed or the block was signed by one of the
+        /// authorized keys, or Err(Error::InvalidSignature(index)) if not.
+        pub(crate) fn check_signature(&self, index: u64, buf: &[u8]) -> Result<()> {
+            if self.header.flags & FLAG_SIGNED != 0 {
+                let (message, trailer) = buf.split_at(DIGEST_SIZE + self.payload_size());
+                #[cfg(feature = "signing")]
+                {
+                    if self.authorized_keys.is_empty() {
+                        return Err(Error::NoAuthorizedKeys);
+                    } else if !signing::verify(
+                        &self.authorized_keys,
+                        message,
+                        &trailer[0..SIGNATURE_SIZE],
+                    ) {
+                        return Err(Error::InvalidSignature(index));
+                    }
+                }
+                #[cfg(not(feature = "signing"))]
+                {
+                    let _ = (index, message, trailer);
+                    return Err(Error::FeatureNotEnabled("signing"));
+                }
+            }
+            Ok(())
+        }
+
+        /// Reads the entire block located at ```index``` into ```buf``` without using or moving the
+        /// stream position, so that many threads can read through a shared reference at once.
+        #[cfg(unix)]
+        pub(crate) fn pread_block(&self, index: u64, buf: &mut [u8]) -> Result<()> {
+            let pos: u64 = index
+                .checked_mul(self.block_size as u64)
+                .ok_or(Error::IntegerOverflow)?;
+            self.inner.read_exact_at(buf, pos)?;
+            self.check_checksum(index, buf)
+        }
+
+        /// Writes the entire block in ```buf``` at ```index``` without using or moving the
+        /// stream position.
+        #[cfg(unix)]
+        pub(crate) fn pwrite_block(&self, index: u64, buf: &[u8]) -> Result<()> {
+            let pos: u64 = index
+                .checked_mul(self.block_size as u64)
+                .ok_or(Error::IntegerOverflow)?;
+            self.inner.write_all_at(buf, pos).map_err(Error::from)
+        }
+
+        /// Opens an existing segment at ```path``` that continues this blockchain. The segment has no
+        /// header of its own, so it shares the header and keys of this file.
+        pub(crate) fn open_segment(&self, path: &Path) -> Result<File> {
+            let file: fs::File = fs::File::options().write(true).read(true).open(path)?;
+            Self::validate_size(&file, self.block_size)?;
+            Ok(self.segment(file, path))
+        }
+
+        /// Opens an existing segment at ```path``` that continues a blockchain whose genisis block,
+        /// and so its header, has been pruned. ```header``` is a copy of that header.
+        pub(crate) fn open_pruned(path: &Path, header: &[u8]) -> Result<File> {
+            let header: Header = Header::read(header)?;
+            let block_size: usize = header.block_size as usize;
+            let file: fs::File = fs::File::options().write(true).read(true).open(path)?;
+            Self::validate_size(&file, block_size)?;
+            Ok(Self {
+                inner: file,
+                block_size,
+                path: path.to_path_buf(),
+                header,
+                #[cfg(feature = "signing")]
+                signing_key: None,
+                #[cfg(feature = "signing")]
+                authorized_keys: Vec::new(),
+                #[cfg(feature = "encryption")]
+                encryption_key: None,
+            })
+        }
+
+        /// Copies the header of this blockchain, as it is stored in the genisis block, into ```buf```.
+        pub(crate) fn write_header(&self, buf: &mut [u8]) {
+            self.header.write(buf)
+        }
+
+        /// Creates a new, empty segment at ```path``` that continues this blockchain.
+        pub(crate) fn create_segment(&self, path: &Path) -> Result<File> {
+            let file: fs::File = fs::File::options()
+                .write(true)
+                .read(true)
+                .create_new(true)
+                .open(path)?;
+            Ok(self.segment(file, path))
+        }
+
+        /// Wraps ```inner``` in a ```File``` that shares the header and keys of this file.
+        fn segment(&self, inner: fs::File, path: &Path) -> File {
+            Self {
+                inner,
+                block_size: self.block_size,
+                path: path.to_path_buf(),
+                header: self.header,
+                #[cfg(feature = "signing")]
+                signing_key: self.signing_key.clone(),
+                #[cfg(feature = "signing")]
+                authorized_keys: self.authorized_keys.clone(),
+                #[cfg(feature = "encryption")]
+                encryption_key: self.encryption_key,
+            }
+        }
+
         /// Returns Ok(()) if the file is not empty and the total files size is an even multiple of the block size.
         fn validate_size(file: &fs::File, block_size: usize) -> Result<()> {
             let size: u64 = file.metadata()?.len();
@@ -236,18 +934,31 @@ pub mod io {
         }
     }
 
+    /// The blocks found by ```Reader::range_by()```.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct KeyRange {
+        /// The indexes of the blocks whose key lies in the range, in ascending order.
+        pub blocks: Vec<u64>,
+        /// True if the keys were found not to be in order, so every block was scanned instead of
+        /// using a binary search.
+        pub scanned: bool,
+    }
+
     /// A struct that wraps a ```io::Bufreader```
     #[derive(Debug)]
     pub struct Reader<'a> {
         inner: BufReader<&'a mut File>,
+        buf: Vec<u8>,
     }
 
     #[allow(dead_code)]
     impl<'a> Reader<'a> {
         /// Creates and returns a new reader object from a ```bc_io::io::File``` object.
         pub fn new(file: &'a mut File) -> Reader<'a> {
+            let block_size: usize = file.block_size();
             Self {
                 inner: BufReader::new(file),
+                buf: vec![0; block_size],
             }
         }
 
@@ -257,6 +968,12 @@ pub mod io {
             self.inner.get_ref().block_size()
         }
 
+        /// Returns the size of the data section of each block in bytes.
+        #[inline]
+        pub fn data_size(&self) -> usize {
+            self.inner.get_ref().data_size()
+        }
+
         /// Returns the total number of blocks in the stream.
         #[inline]
         pub fn block_count(&self) -> Result<u64> {
@@ -269,6 +986,141 @@ pub mod io {
             self.inner.get_ref().size()
         }
 
+        /// Returns the path of the underlying blockchain file.
+        #[inline]
+        pub fn path(&self) -> &Path {
+            self.inner.get_ref().path()
+        }
+
+        /// Returns the indexes of the blocks whose key, extracted from their data sections by
+        /// ```extract```, lies in the range [```lo```, ```hi```). The keys are assumed to be
+        /// non-decreasing, such as timestamps, so the range is found by binary search. The order of
+        /// every key read is verified as it goes, and if it is violated every block is scanned
+        /// instead and ```KeyRange::scanned``` is set, so that the caller can report that the
+        /// keys are out of order. Blocks that are never read are not verified.
+        pub fn range_by<K, F>(&mut self, extract: F, lo: &K, hi: &K) -> Result<KeyRange>
+        where
+            K: Ord,
+            F: Fn(&[u8]) -> Result<K>,
+        {
+            if hi <= lo {
+                return Ok(KeyRange {
+                    blocks: Vec::new(),
+                    scanned: false,
+                });
+            }
+            let block_count: u64 = self.block_count()?;
+            let mut keys: BTreeMap<u64, K> = BTreeMap::new();
+            if let Some(start) = self.lower_bound(&extract, lo, block_count, &mut keys)? {
+                if let Some(end) = self.lower_bound(&extract, hi, block_count, &mut keys)? {
+                    // verify the blocks in the range along with the one on either side of it
+                    let first: u64 = start.saturating_sub(1);
+                    let last: u64 = block_count.min(end + 1);
+                    let mut in_order: bool = start <= end;
+                    for index in first..last {
+                        if !in_order || !self.key_at(&extract, index, &mut keys)? {
+                            in_order = false;
+                            break;
+                        }
+                    }
+                    if in_order {
+                        return Ok(KeyRange {
+                            blocks: (start..end).collect(),
+                            scanned: false,
+                        });
+                    }
+                }
+            }
+            let mut found: Vec<u64> = Vec::new();
+            let mut data: Vec<u8> = vec![0; self.data_size()];
+            self.rewind()?;
+            for index in 0..block_count {
+                self.read_data(&mut data)?;
+                let key: K = extract(&data)?;
+                if *lo <= key && key < *hi {
+                    found.push(index);
+                }
+            }
+            Ok(KeyRange {
+                blocks: found,
+                scanned: true,
+            })
+        }
+
+        /// Returns the index of the first block whose key is not less than ```key```, or None if
+        /// the keys read along the way are not in order.
+        fn lower_bound<K, F>(
+            &mut self,
+            extract: &F,
+            key: &K,
+            block_count: u64,
+            keys: &mut BTreeMap<u64, K>,
+        ) -> Result<Option<u64>>
+        where
+            K: Ord,
+            F: Fn(&[u8]) -> Result<K>,
+        {
+            let (mut lo, mut hi) = (0, block_count);
+            while lo < hi {
+                let mid: u64 = lo + (hi - lo) / 2;
+                if !self.key_at(extract, mid, keys)? {
+                    return Ok(None);
+                } else if keys[&mid] < *key {
+                    lo = mid + 1;
+                } else {
+                    hi = mid;
+                }
+            }
+            Ok(Some(lo))
+        }
+
+        /// Reads the key of the block located at ```index``` into ```keys```, unless it is already
+        /// there. Returns false if it is out of order with the nearest keys read before it.
+        fn key_at<K, F>(
+            &mut self,
+            extract: &F,
+            index: u64,
+            keys: &mut BTreeMap<u64, K>,
+        ) -> Result<bool>
+        where
+            K: Ord,
+            F: Fn(&[u8]) -> Result<K>,
+        {
+            if let Entry::Vacant(entry) = keys.entry(index) {
+                let mut data: Vec<u8> = vec![0; self.data_size()];
+                self.read_data_at(index, &mut data)?;
+                entry.insert(extract(&data)?);
+            }
+            let key: &K = &keys[&index];
+            let before: bool = keys
+                .range(..index)
+                .next_back()
+                .is_none_or(|(_, k)| k <= key);
+            let after: bool = keys.range(index + 1..).next().is_none_or(|(_, k)| key <= k);
+            Ok(before && after)
+        }
+
+        /// Returns the indexes of the blocks whose key in the secondary index ```index``` is ```key```,
+        /// after adding any blocks that have been appended since the index was last updated.
+        pub fn find_by_key(&mut self, index: &mut KeyIndex, key: &[u8]) -> Result<Vec<u64>> {
+            index.update(self)?;
+            Ok(index.get(key).to_vec())
+        }
+
+        /// Returns the indexes of the blocks whose key in the bloom index ```index``` is ```key```,
+        /// reading only the groups of blocks whose filter may contain it.
+        pub fn find_by_bloom(&mut self, index: &mut BloomIndex, key: &[u8]) -> Result<Vec<u64>> {
+            index.find(self, key)
+        }
+
+        /// Returns true if the data section of each block is stored exactly as it was appended,
+        /// neither compressed nor encrypted, so that it can be viewed in place.
+        #[inline]
+        pub fn is_plain(&self) -> bool {
+            let file: &File = self.inner.get_ref();
+            file.compression() == Compression::None && !file.is_encrypted()
+        }
+
         /// Returns the current position in the byte stream. If the position is not an even
         /// multiple of the block size, then Err(Error::BadStreamPosition(pos)) is returned.
         #[inline]
@@ -282,6 +1134,44 @@ pub mod io {
             }
         }
 
+        /// Returns a blocking iterator over the blocks appended to the stream after its
+        /// current last block, typically by another process. See ```Follow``` for details.
+        pub fn follow(&mut self) -> Result<Follow<'_, 'a>> {
+            let index: u64 = self.stream_size()? / self.block_size() as u64;
+            Ok(self.follow_from(index))
+        }
+
+        /// Returns a blocking iterator over the blocks in the stream starting at ```index```,
+        /// which waits for new blocks to be appended once it reaches the end of the stream.
+        pub fn follow_from(&mut self, index: u64) -> Follow<'_, 'a> {
+            Follow::new(self, index)
+        }
+
+        /// Returns an iterator over every block in the stream, starting at the last block and
+        /// ending at the genisis block.
+        pub fn blocks_rev(&mut self) -> Result<BlocksRev<'_, 'a>> {
+            self.last_n(u64::MAX)
+        }
+
+        /// Returns an iterator over the last ```n``` blocks in the stream, or every block if there
+        /// are fewer than ```n```, starting at the last block.
+        pub fn last_n(&mut self, n: u64) -> Result<BlocksRev<'_, 'a>> {
+            let end: u64 = self.block_count()?;
+            Ok(BlocksRev::new(self, end.saturating_sub(n), end))
+        }
+
+        /// Returns an iterator over the data section of every block in the stream deserialized as
+        /// a ```T```, starting at the last block and ending at the genisis block.
+        pub fn blocks_rev_as<T: Deserialize>(&mut self) -> Result<DataRev<'_, 'a, T>> {
+            Ok(DataRev::new(self.blocks_rev()?))
+        }
+
+        /// Returns an iterator over the data sections of the last ```n``` blocks in the stream
+        /// deserialized as a ```T```, starting at the last block.
+        pub fn last_n_as<T: Deserialize>(&mut self, n: u64) -> Result<DataRev<'_, 'a, T>> {
+            Ok(DataRev::new(self.last_n(n)?))
+        }
+
         /// Calls ```rewind()``` on the underlying blockchain file.
         pub fn rewind(&mut self) -> Result<()> {
             self.inner.rewind().map_err(Error::from)
@@ -297,10 +1187,15 @@ pub mod io {
 
         /// Reads the entire block located at the current stream position and copies it into ```buf```.
         /// Returns Ok(()) on success, or Err(Error) on failure. The length of ```buf```
-        /// must be exactly equal to the total block size.
+        /// must be exactly equal to the total block size. If the blockchain has checksums and the
+        /// block's checksum does not match, then Err(Error::InvalidChecksum(index)) is returned.
         pub fn read_block(&mut self, buf: &mut [u8]) -> Result<()> {
             if buf.len() != self.block_size() {
                 Err(Error::InvalidSliceLength)
+            } else if self.inner.get_ref().has_checksums() {
+                let index: u64 = self.stream_position()? / self.block_size() as u64;
+                self.inner.read_exact(buf)?;
+                self.inner.get_ref().check_checksum(index, buf)
             } else {
                 self.inner.read_exact(buf).map_err(Error::from)
             }
@@ -316,20 +1211,46 @@ pub mod io {
 
         /// Reads the data section of the block located at the current stream position and
         /// copies it into ```buf```. Returns Ok(()) on success, or Err(Error) on failure.
-        /// The length of ```buf``` must be exactly equal to the total block size minus the
-        /// size of a SHA-256 digest (32 bytes).
+        /// The length of ```buf``` must be exactly equal to ```data_size()```.
         pub fn read_data(&mut self, buf: &mut [u8]) -> Result<()> {
-            if buf.len() != self.block_size() - DIGEST_SIZE {
+            let data_size: usize = self.data_size();
+            if buf.len() != data_size {
                 Err(Error::InvalidSliceLength)
             } else {
-                self.inner.seek(SeekFrom::Current(DIGEST_SIZE as i64))?;
-                self.inner.read_exact(buf).map_err(Error::from)
+                let mut block: Vec<u8> = std::mem::take(&mut self.buf);
+                let result: Result<()> = self
+                    .read_block(&mut block)
+                    .and_then(|_| self.decode_data(&block, buf));
+                self.buf = block;
+                result
+            }
+        }
+
+        /// Copies the data section of the entire block in ```block``` into ```buf```, decompressing it
+        /// if the blockchain is compressed. The length of ```buf``` must be exactly equal to
+        /// ```data_size()```.
+        pub fn decode_data(&self, block: &[u8], buf: &mut [u8]) -> Result<()> {
+            let file: &File = self.inner.get_ref();
+            if block.len() != self.block_size() || buf.len() != self.data_size() {
+                Err(Error::InvalidSliceLength)
+            } else {
+                file.decode_data(block, buf)
+            }
+        }
+
+        /// Returns the hash of the entire block in ```block```. This is the hash stored in the next
+        /// block, calculated over either the stored or the uncompressed bytes as recorded in the header.
+        pub fn block_hash(&self, block: &[u8]) -> Result<Digest> {
+            if block.len() != self.block_size() {
+                Err(Error::InvalidSliceLength)
+            } else {
+                self.inner.get_ref().block_hash(block)
             }
         }
 
         /// Reads the data section of of the block located at ```index``` and copies it into ```buf```.
         /// Returns Ok(()) on success, or Err(Error) on failure. The length of ```buf``` must be
-        /// exactly equal to the total block size minus the size of a SHA-256 digest (32 bytes).
+        /// exactly equal to ```data_size()```.
         pub fn read_data_at(&mut self, index: u64, buf: &mut [u8]) -> Result<()> {
             self.seek(index)?;
             self.read_data(buf)
@@ -338,47 +1259,57 @@ pub mod io {
         /// Calculates the hash of the block located at ```index - 1``` and compares
         /// it to the previous block's hash stored in the block located at ```index```.
         /// Returns Ok(()) if the hashs are identical, or Err(Error::InvalidBlockHash(index)) if not.
+        /// If the blockchain has checksums, the checksums of both blocks are verified first, and
+        /// Err(Error::InvalidChecksum(i)) is returned if either block was corrupted on disk. If the
+        /// blockchain is signed, the signature of the block is also verified against the authorized
+        /// keys and Err(Error::InvalidSignature(index)) is returned if it is not valid.
         pub fn validate_block_at(&mut self, index: u64) -> Result<()> {
             let block_size: usize = self.block_size();
+            let mut buf: Vec<u8> = vec![0; block_size];
             if index >= self.block_count()? {
                 Err(Error::BlockNumDoesNotExist)
             } else if index == 0 {
-                Ok(()) // the genisis block is inherently always valid
+                // the genisis block has no previous block, so only its trailer can be checked
+                self.inner.rewind()?;
+                self.read_block(&mut buf[0..block_size])?;
+                self.inner.get_ref().check_signature(index, &buf)
             } else {
-                let pos: u64 = (index - 1)
-                    .checked_mul(block_size as u64)
-                    .ok_or(Error::IntegerOverflow)?;
-                self.inner.seek(SeekFrom::Start(pos))?;
-                let mut buf: Vec<u8> = vec![0; block_size];
-                self.inner.read_exact(&mut buf[0..block_size])?;
-                let d1: Digest = Digest::from(&buf[0..block_size]);
-                self.inner.read_exact(&mut buf[0..block_size])?;
+                self.seek(index - 1)?;
+                self.read_block(&mut buf[0..block_size])?;
+                let d1: Digest = self.block_hash(&buf[0..block_size])?;
+                self.read_block(&mut buf[0..block_size])?;
                 let d2: Digest = Digest::deserialize(&buf[0..DIGEST_SIZE])?;
                 if d1 != d2 {
                     Err(Error::InvalidBlockHash(index))
                 } else {
-                    Ok(())
+                    self.inner.get_ref().check_signature(index, &buf)
                 }
             }
         }
 
         /// Iterates over each block in the range [1..], calculates the hash of the previous block, and
         /// compares it to the previous block hash stored in the current block. If it encounters two hashs
-        /// that are not identical, then Err(Error::InvalidBlockHash(b)) is returned. Otherwise Ok(())
-        /// is returned when the iteration is complete.
+        /// that are not identical, then Err(Error::InvalidBlockHash(b)) is returned. If the blockchain
+        /// has checksums, each block's checksum is verified before its hash, so a block that was
+        /// corrupted on disk is reported as Err(Error::InvalidChecksum(b)) rather than as a broken
+        /// link. If the blockchain is signed, the signature of every block is also verified and
+        /// Err(Error::InvalidSignature(b)) is returned for the first one that is not valid.
+        /// Otherwise Ok(()) is returned when the iteration is complete.
         pub fn validate_all_blocks(&mut self) -> Result<()> {
             let block_size: usize = self.block_size();
             let block_count: u64 = self.block_count()?;
             self.inner.rewind()?;
             let mut buf: Vec<u8> = vec![0; block_size];
-            self.inner.read_exact(&mut buf[0..block_size])?; // read the genisis block
+            self.read_block(&mut buf[0..block_size])?; // read the genisis block
+            self.inner.get_ref().check_signature(0, &buf)?;
             for b in (0..block_count).skip(1) {
-                let prev_digest: Digest = Digest::from(&buf[0..block_size]);
-                self.inner.read_exact(&mut buf[0..block_size])?;
+                let prev_digest: Digest = self.block_hash(&buf[0..block_size])?;
+                self.read_block(&mut buf[0..block_size])?;
                 let digest: Digest = Digest::deserialize(&buf[0..DIGEST_SIZE])?;
                 if digest != prev_digest {
                     return Err(Error::InvalidBlockHash(b));
                 }
+                self.inner.get_ref().check_signature(b, &buf)?;
             }
             Ok(())
         }
@@ -389,6 +1320,7 @@ pub mod io {
         inner: BufWriter<&'a mut File>,
         last_hash: Digest,
         buf: Vec<u8>,
+        subscribers: Vec<Sender<(u64, Digest)>>,
     }
 
     #[allow(dead_code)]
@@ -397,21 +1329,42 @@ pub mod io {
         pub fn new(file: &'a mut File) -> Result<Self> {
             let block_size: usize = file.block_size();
             let mut buf: Vec<u8> = vec![0; block_size];
+            let index: u64 = file.block_count()? - 1;
             file.inner.seek(SeekFrom::End(-(block_size as i64)))?;
             file.inner.read_exact(&mut buf[0..block_size])?;
+            file.check_checksum(index, &buf)?;
             Ok(Self {
+                last_hash: file.block_hash(&buf[0..block_size])?,
                 inner: BufWriter::new(file),
-                last_hash: Digest::from(&buf[0..block_size]),
                 buf,
+                subscribers: Vec::new(),
             })
         }
 
+        /// Creates and returns a new ```Writer``` that appends blocks to ```file``` following a
+        /// block whose hash is ```last_hash```, which need not be stored in ```file```.
+        pub(crate) fn continue_from(file: &'a mut File, last_hash: Digest) -> Self {
+            let block_size: usize = file.block_size();
+            Self {
+                inner: BufWriter::new(file),
+                last_hash,
+                buf: vec![0; block_size],
+                subscribers: Vec::new(),
+            }
+        }
+
         /// Returns the block size for the underlying blockchain in bytes.
         #[inline]
         pub fn block_size(&self) -> usize {
             self.inner.get_ref().block_size()
         }
 
+        /// Returns the size of the data section of each block in bytes.
+        #[inline]
+        pub fn data_size(&self) -> usize {
+            self.inner.get_ref().data_size()
+        }
+
         /// Returns the total number of blocks in the stream.
         #[inline]
         pub fn block_count(&self) -> Result<u64> {
@@ -437,24 +1390,98 @@ pub mod io {
             }
         }
 
+        /// Returns the hash of the last block in the stream.
+        #[inline]
+        pub fn last_hash(&self) -> &Digest {
+            &self.last_hash
+        }
+
+        /// Returns a channel that receives the index and hash of each block appended by this
+        /// writer. A block is only sent after it has been flushed and synced to disk. Receivers
+        /// that have been dropped are removed on the next append.
+        pub fn subscribe(&mut self) -> Receiver<(u64, Digest)> {
+            let (sender, receiver) = mpsc::channel();
+            self.subscribers.push(sender);
+            receiver
+        }
+
         /// Writes a new block to the end of the stream. You need not concern yourself with the previous
         /// block hash when calling this method. ```Writer``` takes care of this for you. The ```data`` arg
         /// should contains the serialized data section of the new block. As suchy, the length of ```data```
-        /// must be exactly equal to the total block size minus the size of a SHA-256 digest (32 bytes).
-        /// If not, then Err(Error::InvalidSliceLength) is returned.
+        /// must be exactly equal to ```data_size()```. If not, then Err(Error::InvalidSliceLength) is
+        /// returned. If the blockchain is signed, the block is signed with the file's signing key.
         pub fn append(&mut self, data: &mut [u8]) -> Result<()> {
-            let block_size: usize = self.block_size();
-            if data.len() + DIGEST_SIZE != block_size {
+            if data.len() != self.data_size() {
                 Err(Error::InvalidSliceLength)
             } else {
-                self.last_hash.serialize(&mut self.buf[0..DIGEST_SIZE])?;
-                self.buf[DIGEST_SIZE..block_size].clone_from_slice(data);
-                self.inner.seek(SeekFrom::End(0))?;
-                self.inner.write_all(&self.buf[0..block_size])?;
-                self.inner.flush()?;
-                self.last_hash = Digest::from(&self.buf[0..block_size]);
-                Ok(())
+                let hash: Digest =
+                    self.inner
+                        .get_ref()
+                        .build_block(&self.last_hash, data, &mut self.buf)?;
+                self.write_block(hash)
+            }
+        }
+
+        /// Writes the entire block in ```block```, such as one received from another copy of the
+        /// blockchain, to the end of the stream as is. Its previous block hash must be the hash of
+        /// the last block in the stream, or Err(Error::InvalidBlockHash(index)) is returned. Its
+        /// checksum and signature are verified the same way as ```Reader::validate_block_at()```.
+        pub fn append_block(&mut self, block: &[u8]) -> Result<()> {
+            if block.len() != self.block_size() {
+                return Err(Error::InvalidSliceLength);
+            }
+            let index: u64 = self.block_count()?;
+            let file: &File = self.inner.get_ref();
+            file.check_checksum(index, block)?;
+            if Digest::deserialize(&block[0..DIGEST_SIZE])? != self.last_hash {
+                return Err(Error::InvalidBlockHash(index));
+            }
+            file.check_signature(index, block)?;
+            let hash: Digest = file.block_hash(block)?;
+            self.buf.copy_from_slice(block);
+            self.write_block(hash)
+        }
+
+        /// Writes the entire block in ```buf```, whose hash is ```hash```, to the end of the stream
+        /// and notifies the subscribers.
+        fn write_block(&mut self, hash: Digest) -> Result<()> {
+            let block_size: usize = self.block_size();
+            self.inner.seek(SeekFrom::End(0))?;
+            self.inner.write_all(&self.buf[0..block_size])?;
+            self.inner.flush()?;
+            self.last_hash = hash;
+            if !self.subscribers.is_empty() {
+                self.inner.get_ref().inner.sync_data()?;
+                let index: u64 = self.block_count()? - 1;
+                self.subscribers
+                    .retain(|s| s.send((index, self.last_hash.clone())).is_ok());
             }
+            Ok(())
         }
     }
 }
+
+#[cfg(all(feature = "tokio", unix))]
+pub mod async_io;
+pub mod bloom;
+pub mod compress;
+mod crc;
+pub mod encryption;
+pub mod export;
+pub mod follow;
+pub mod import;
+pub mod index;
+pub mod layout;
+pub mod merkle;
+pub mod migrate;
+pub mod replicate;
+pub mod reverse;
+pub mod schema;
+pub mod segment;
+#[cfg(feature = "serde")]
+pub mod serde_bridge;
+#[cfg(unix)]
+pub mod shared;
+#[cfg(feature = "signing")]
+pub mod signing;
+pub mod view;
@@ -21,18 +21,15 @@
 /// SOFTWARE.
 
 use bc_hash::sha256::{Digest, DIGEST_SIZE};
-use bc_io::io::{
-    Deserialize, Error as BcError, File, Reader, Result as BcResult, Serialize, Writer,
-};
+use bc_io::export::{self, Encoding, Export, Value};
+use bc_io::io::{Deserialize, File, Reader, Result as BcResult, Serialize, Writer};
+use bc_io::layout::{FieldCursor, Layout};
 use chrono::Utc;
 use std::path::Path;
 
-pub const TIMESTAMP: (usize, usize) = (0, 8);
-pub const USER_ID: (usize, usize) = (8, 16);
-pub const VERSION: (usize, usize) = (16, 24);
-pub const DATA_SIZE: (usize, usize) = (24, 32);
-pub const MERKLE_ROOT: (usize, usize) = (32, 64);
-pub const BLOCK_SIZE: usize = 64;
+// timestamp, user_id, version, data_size, merkle_root
+pub const LAYOUT: Layout = Layout::new().i64().u64().u64().u64().digest();
+pub const BLOCK_SIZE: usize = LAYOUT.size();
 
 #[derive(Debug, Clone)]
 pub struct Block {
@@ -45,17 +42,12 @@ pub struct Block {
 
 impl Serialize for Block {
     fn serialize(&self, buf: &mut [u8]) -> BcResult<()> {
-        if buf.len() != BLOCK_SIZE {
-            Err(BcError::InvalidSliceLength)
-        } else {
-            buf[TIMESTAMP.0..TIMESTAMP.1].clone_from_slice(&self.timestamp.to_le_bytes()[..]);
-            buf[USER_ID.0..USER_ID.1].clone_from_slice(&self.user_id.to_le_bytes()[..]);
-            buf[VERSION.0..VERSION.1].clone_from_slice(&self.version.to_le_bytes()[..]);
-            buf[DATA_SIZE.0..DATA_SIZE.1].clone_from_slice(&self.data_size.to_le_bytes()[..]);
-            self.merkle_root
-                .serialize(&mut buf[MERKLE_ROOT.0..MERKLE_ROOT.1])?;
-            Ok(())
-        }
+        let mut cursor: FieldCursor<&mut [u8]> = FieldCursor::with_layout(buf, &LAYOUT)?;
+        cursor.put_i64_le(self.timestamp)?;
+        cursor.put_u64_le(self.user_id)?;
+        cursor.put_u64_le(self.version)?;
+        cursor.put_u64_le(self.data_size)?;
+        cursor.put_digest(&self.merkle_root)
     }
 }
 
@@ -64,17 +56,25 @@ impl Deserialize for Block {
     where
         Self: Sized,
     {
-        if buf.len() != BLOCK_SIZE {
-            Err(BcError::InvalidSliceLength)
-        } else {
-            Ok(Self {
-                timestamp: i64::from_le_bytes(buf[TIMESTAMP.0..TIMESTAMP.1].try_into().unwrap()),
-                user_id: u64::from_le_bytes(buf[USER_ID.0..USER_ID.1].try_into().unwrap()),
-                version: u64::from_le_bytes(buf[VERSION.0..VERSION.1].try_into().unwrap()),
-                data_size: u64::from_le_bytes(buf[DATA_SIZE.0..DATA_SIZE.1].try_into().unwrap()),
-                merkle_root: Digest::deserialize(&buf[MERKLE_ROOT.0..MERKLE_ROOT.1])?,
-            })
-        }
+        let mut cursor: FieldCursor<&[u8]> = FieldCursor::with_layout(buf, &LAYOUT)?;
+        Ok(Self {
+            timestamp: cursor.get_i64_le()?,
+            user_id: cursor.get_u64_le()?,
+            version: cursor.get_u64_le()?,
+            data_size: cursor.get_u64_le()?,
+            merkle_root: cursor.get_digest()?,
+        })
+    }
+}
+
+impl Export for Block {
+    fn fields(&self) -> Vec<(&'static str, Value)> {
+        vec![
+            ("timestamp", Value::from(self.timestamp)),
+            ("user_id", Value::from(self.user_id)),
+            ("version", Value::from(self.version)),
+            ("data_size", Value::from(self.data_size)),
+        ]
     }
 }
 
@@ -174,5 +174,8 @@ fn main() -> BcResult<()> {
     let obj = Block::deserialize(&data_buf)?;
     println!("Read data {:?}", &obj);
 
+    // export the chain as JSON lines, including the decoded block fields
+    export::to_jsonl_with::<Block, _>(&mut reader, &mut std::io::stdout(), Encoding::Hex)?;
+
     Ok(())
 }
@@ -0,0 +1,384 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+use crate::io::{Error, Reader, Result};
+use bc_hash::sha256::{Digest, DIGEST_SIZE};
+
+/// The byte prepended to a block hash before hashing it into a leaf (RFC 6962).
+const LEAF_PREFIX: u8 = 0x00;
+
+/// The byte prepended to a pair of child hashes before hashing them into a node (RFC 6962).
+const NODE_PREFIX: u8 = 0x01;
+
+/// Returns the leaf hash of a block hash.
+fn hash_leaf(block_hash: &Digest) -> Result<Digest> {
+    let mut buf: [u8; DIGEST_SIZE + 1] = [LEAF_PREFIX; DIGEST_SIZE + 1];
+    block_hash.serialize(&mut buf[1..])?;
+    Ok(Digest::from(&buf[..]))
+}
+
+/// Returns the hash of an interior node from the hashes of its children.
+fn hash_node(left: &Digest, right: &Digest) -> Result<Digest> {
+    let mut buf: [u8; DIGEST_SIZE * 2 + 1] = [NODE_PREFIX; DIGEST_SIZE * 2 + 1];
+    left.serialize(&mut buf[1..DIGEST_SIZE + 1])?;
+    right.serialize(&mut buf[DIGEST_SIZE + 1..])?;
+    Ok(Digest::from(&buf[..]))
+}
+
+/// Returns the largest power of two that is less than ```n```, where ```n``` > 1.
+fn split(n: u64) -> u64 {
+    1 << (63 - (n - 1).leading_zeros())
+}
+
+/// Transmutates the two sizes and the path of a proof into an array of bytes.
+fn encode_proof(a: u64, b: u64, path: &[Digest]) -> Result<Vec<u8>> {
+    let mut buf: Vec<u8> = vec![0; 16 + path.len() * DIGEST_SIZE];
+    buf[0..8].copy_from_slice(&a.to_le_bytes());
+    buf[8..16].copy_from_slice(&b.to_le_bytes());
+    for (digest, chunk) in path.iter().zip(buf[16..].chunks_mut(DIGEST_SIZE)) {
+        digest.serialize(chunk)?;
+    }
+    Ok(buf)
+}
+
+/// Transmutates an array of bytes produced by ```encode_proof()``` into two sizes and a path.
+fn decode_proof(buf: &[u8]) -> Result<(u64, u64, Vec<Digest>)> {
+    if buf.len() < 16 || !(buf.len() - 16).is_multiple_of(DIGEST_SIZE) {
+        return Err(Error::InvalidSliceLength);
+    }
+    let path: Vec<Digest> = buf[16..]
+        .chunks(DIGEST_SIZE)
+        .map(Digest::deserialize)
+        .collect::<std::result::Result<Vec<Digest>, _>>()?;
+    Ok((
+        u64::from_le_bytes(buf[0..8].try_into().unwrap()),
+        u64::from_le_bytes(buf[8..16].try_into().unwrap()),
+        path,
+    ))
+}
+
+/// A Merkle tree over the block hashes of a chain, where leaf ```i``` is the hash of block ```i```.
+/// The tree is built as described in RFC 6962, so its root commits to the entire chain, and
+/// inclusion and consistency proofs can be checked by anyone who knows the roots. Blocks can
+/// only be appended, either with ```push()``` (e.g. with the hashes sent by
+/// ```Writer::subscribe()```) or by catching up with a stream using ```update()```.
+#[derive(Debug, Clone, Default)]
+pub struct MerkleTree {
+    /// Level ```k``` holds the roots of the complete subtrees of 2^k leaves, from left to right.
+    levels: Vec<Vec<Digest>>,
+}
+
+impl MerkleTree {
+    /// Creates and returns a new, empty tree.
+    pub fn new() -> Self {
+        Self { levels: Vec::new() }
+    }
+
+    /// Creates and returns a new tree over every block in the stream.
+    pub fn from_reader(reader: &mut Reader) -> Result<Self> {
+        let mut tree: Self = Self::new();
+        tree.update(reader)?;
+        Ok(tree)
+    }
+
+    /// Returns the number of leaves (blocks) in the tree.
+    #[inline]
+    pub fn len(&self) -> u64 {
+        self.levels.first().map_or(0, |leaves| leaves.len() as u64)
+    }
+
+    /// Returns true if the tree has no leaves.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Appends the hash of the next block in the chain to the tree.
+    pub fn push(&mut self, block_hash: &Digest) -> Result<()> {
+        let mut node: Digest = hash_leaf(block_hash)?;
+        for k in 0.. {
+            if self.levels.len() == k {
+                self.levels.push(Vec::new());
+            }
+            let level: &mut Vec<Digest> = &mut self.levels[k];
+            level.push(node);
+            let len: usize = level.len();
+            if !len.is_multiple_of(2) {
+                break;
+            }
+            node = hash_node(&level[len - 2], &level[len - 1])?;
+        }
+        Ok(())
+    }
+
+    /// Appends the hashes of any blocks in the stream that are not yet in the tree.
+    /// Returns the number of blocks that were added.
+    pub fn update(&mut self, reader: &mut Reader) -> Result<u64> {
+        let block_count: u64 = reader.block_count()?;
+        let start: u64 = self.len();
+        if start > block_count {
+            return Err(Error::BlockNumDoesNotExist);
+        }
+        let mut buf: Vec<u8> = vec![0; reader.block_size()];
+        reader.seek(start)?;
+        for _ in start..block_count {
+            reader.read_block(&mut buf)?;
+            self.push(&reader.block_hash(&buf)?)?;
+        }
+        Ok(block_count - start)
+    }
+
+    /// Returns the root of the tree over all of its leaves.
+    pub fn root(&self) -> Result<Digest> {
+        self.root_at(self.len())
+    }
+
+    /// Returns the root of the tree as it was when it had ```size``` leaves.
+    pub fn root_at(&self, size: u64) -> Result<Digest> {
+        if size > self.len() {
+            Err(Error::BlockNumDoesNotExist)
+        } else {
+            self.subtree(0, size)
+        }
+    }
+
+    /// Returns the root of the subtree over the leaves in the range [start..end). Every subtree
+    /// visited by RFC 6962 starts at a multiple of its largest power of two, so its complete
+    /// subtrees can be looked up in ```levels``` and only the right edge needs to be hashed.
+    fn subtree(&self, start: u64, end: u64) -> Result<Digest> {
+        let n: u64 = end - start;
+        if n == 0 {
+            Ok(Digest::from(&[][..]))
+        } else if n.is_power_of_two() {
+            let k: u32 = n.trailing_zeros();
+            Ok(self.levels[k as usize][(start >> k) as usize].clone())
+        } else {
+            let k: u64 = split(n);
+            hash_node(
+                &self.subtree(start, start + k)?,
+                &self.subtree(start + k, end)?,
+            )
+        }
+    }
+
+    /// Returns a proof that block ```index``` is included in the tree over all of its leaves.
+    pub fn prove(&self, index: u64) -> Result<InclusionProof> {
+        self.prove_at(index, self.len())
+    }
+
+    /// Returns a proof that block ```index``` is included in the tree as it was when it had
+    /// ```size``` leaves.
+    pub fn prove_at(&self, index: u64, size: u64) -> Result<InclusionProof> {
+        if index >= size || size > self.len() {
+            Err(Error::BlockNumDoesNotExist)
+        } else {
+            let mut path: Vec<Digest> = Vec::new();
+            self.inclusion_path(index, 0, size, &mut path)?;
+            Ok(InclusionProof { index, size, path })
+        }
+    }
+
+    /// Returns a proof that the tree as it was when it had ```first``` leaves is a prefix of the
+    /// tree as it was when it had ```second``` leaves, where 0 < ```first``` <= ```second```.
+    pub fn prove_consistency(&self, first: u64, second: u64) -> Result<ConsistencyProof> {
+        if first == 0 || first > second || second > self.len() {
+            Err(Error::BlockNumDoesNotExist)
+        } else {
+            let mut path: Vec<Digest> = Vec::new();
+            if first < second {
+                self.consistency_path(first, 0, second, true, &mut path)?;
+            }
+            Ok(ConsistencyProof {
+                first,
+                second,
+                path,
+            })
+        }
+    }
+
+    /// Appends the consistency path between the first ```m``` leaves of the subtree [start..end)
+    /// and the whole subtree to ```path```. This is SUBPROOF from RFC 6962, where ```complete```
+    /// is true if the first ```m``` leaves form a subtree whose root the verifier already knows.
+    fn consistency_path(
+        &self,
+        m: u64,
+        start: u64,
+        end: u64,
+        complete: bool,
+        path: &mut Vec<Digest>,
+    ) -> Result<()> {
+        let n: u64 = end - start;
+        if m == n {
+            if !complete {
+                path.push(self.subtree(start, end)?);
+            }
+        } else {
+            let k: u64 = split(n);
+            if m <= k {
+                self.consistency_path(m, start, start + k, complete, path)?;
+                path.push(self.subtree(start + k, end)?);
+            } else {
+                self.consistency_path(m - k, start + k, end, false, path)?;
+                path.push(self.subtree(start, start + k)?);
+            }
+        }
+        Ok(())
+    }
+
+    /// Appends the audit path of leaf ```m``` in the subtree [start..end) to ```path```,
+    /// from the leaf up to the root of the subtree.
+    fn inclusion_path(&self, m: u64, start: u64, end: u64, path: &mut Vec<Digest>) -> Result<()> {
+        let n: u64 = end - start;
+        if n > 1 {
+            let k: u64 = split(n);
+            if m < k {
+                self.inclusion_path(m, start, start + k, path)?;
+                path.push(self.subtree(start + k, end)?);
+            } else {
+                self.inclusion_path(m - k, start + k, end, path)?;
+                path.push(self.subtree(start, start + k)?);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A proof that the block at ```index``` is included in a tree of ```size``` leaves.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InclusionProof {
+    pub index: u64,
+    pub size: u64,
+    pub path: Vec<Digest>,
+}
+
+impl InclusionProof {
+    /// Returns true if this proof shows that the block with hash ```block_hash``` is included
+    /// in the tree with root ```root```. The algorithm is the one given in RFC 9162.
+    pub fn verify(&self, block_hash: &Digest, root: &Digest) -> Result<bool> {
+        if self.index >= self.size {
+            return Ok(false);
+        }
+        let mut f: u64 = self.index;
+        let mut s: u64 = self.size - 1;
+        let mut r: Digest = hash_leaf(block_hash)?;
+        for p in self.path.iter() {
+            if s == 0 {
+                return Ok(false);
+            }
+            if f & 1 == 1 || f == s {
+                r = hash_node(p, &r)?;
+                while f & 1 == 0 && f != 0 {
+                    f >>= 1;
+                    s >>= 1;
+                }
+            } else {
+                r = hash_node(&r, p)?;
+            }
+            f >>= 1;
+            s >>= 1;
+        }
+        Ok(s == 0 && r == *root)
+    }
+
+    /// Transmutates the proof into an array of bytes: the index and size as little endian
+    /// u64s, followed by each hash in the path.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        encode_proof(self.index, self.size, &self.path)
+    }
+
+    /// Transmutates an array of bytes produced by ```to_bytes()``` into a new proof.
+    pub fn from_bytes(buf: &[u8]) -> Result<Self> {
+        let (index, size, path) = decode_proof(buf)?;
+        Ok(Self { index, size, path })
+    }
+}
+
+/// A proof that the tree of ```first``` leaves is a prefix of the tree of ```second``` leaves,
+/// meaning the chain was only appended to between the two sizes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConsistencyProof {
+    pub first: u64,
+    pub second: u64,
+    pub path: Vec<Digest>,
+}
+
+impl ConsistencyProof {
+    /// Returns true if this proof shows that the tree with root ```first_root``` is a prefix of
+    /// the tree with root ```second_root```. Only the two roots are needed, not the chain itself.
+    /// The algorithm is the one given in RFC 9162.
+    pub fn verify(&self, first_root: &Digest, second_root: &Digest) -> Result<bool> {
+        if self.first == 0 || self.first > self.second {
+            return Ok(false);
+        } else if self.first == self.second {
+            return Ok(self.path.is_empty() && first_root == second_root);
+        } else if self.path.is_empty() {
+            return Ok(false);
+        }
+        let mut path: Vec<&Digest> = Vec::with_capacity(self.path.len() + 1);
+        if self.first.is_power_of_two() {
+            path.push(first_root);
+        }
+        path.extend(self.path.iter());
+        let mut f: u64 = self.first - 1;
+        let mut s: u64 = self.second - 1;
+        while f & 1 == 1 {
+            f >>= 1;
+            s >>= 1;
+        }
+        let mut fr: Digest = path[0].clone();
+        let mut sr: Digest = path[0].clone();
+        for c in path.into_iter().skip(1) {
+            if s == 0 {
+                return Ok(false);
+            }
+            if f & 1 == 1 || f == s {
+                fr = hash_node(c, &fr)?;
+                sr = hash_node(c, &sr)?;
+                while f & 1 == 0 && f != 0 {
+                    f >>= 1;
+                    s >>= 1;
+                }
+            } else {
+                sr = hash_node(&sr, c)?;
+            }
+            f >>= 1;
+            s >>= 1;
+        }
+        Ok(fr == *first_root && sr == *second_root && s == 0)
+    }
+
+    /// Transmutates the proof into an array of bytes: the first and second sizes as little
+    /// endian u64s, followed by each hash in the path.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        encode_proof(self.first, self.second, &self.path)
+    }
+
+    /// Transmutates an array of bytes produced by ```to_bytes()``` into a new proof.
+    pub fn from_bytes(buf: &[u8]) -> Result<Self> {
+        let (first, second, path) = decode_proof(buf)?;
+        Ok(Self {
+            first,
+            second,
+            path,
+        })
+    }
+}
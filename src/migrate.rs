@@ -0,0 +1,154 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+use crate::import::RawData;
+use crate::io::{Error, File, Options, Reader, Result, Writer};
+use bc_hash::sha256::{Digest, DIGEST_SIZE};
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The size of a migration record in bytes.
+const MIGRATION_SIZE: usize = 8 + DIGEST_SIZE + DIGEST_SIZE;
+
+/// A record of a chain that was rewritten by ```rewrite()```, which maps the tip of the old
+/// chain to the tip of the new one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Migration {
+    /// The number of blocks in both chains.
+    pub block_count: u64,
+    /// The hash of the last block in the old chain.
+    pub old_tip: Digest,
+    /// The hash of the last block in the new chain.
+    pub new_tip: Digest,
+}
+
+impl Migration {
+    /// Returns the path of the migration record written alongside the new chain at ```path```.
+    pub fn path(path: &Path) -> PathBuf {
+        let mut path: OsString = path.as_os_str().to_os_string();
+        path.push(".migration");
+        PathBuf::from(path)
+    }
+
+    /// Reads the migration record written alongside the new chain at ```path```.
+    pub fn read(path: &Path) -> Result<Self> {
+        Self::from_bytes(&fs::read(Self::path(path))?)
+    }
+
+    /// Transmutates the migration record into a vector of bytes.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut buf: Vec<u8> = vec![0; MIGRATION_SIZE];
+        buf[0..8].copy_from_slice(&self.block_count.to_le_bytes());
+        self.old_tip.serialize(&mut buf[8..8 + DIGEST_SIZE])?;
+        self.new_tip.serialize(&mut buf[8 + DIGEST_SIZE..])?;
+        Ok(buf)
+    }
+
+    /// Transmutates a slice of bytes created by ```to_bytes()``` back into a migration record.
+    pub fn from_bytes(buf: &[u8]) -> Result<Self> {
+        if buf.len() != MIGRATION_SIZE {
+            Err(Error::InvalidSliceLength)
+        } else {
+            Ok(Self {
+                block_count: u64::from_le_bytes(buf[0..8].try_into().unwrap()),
+                old_tip: Digest::deserialize(&buf[8..8 + DIGEST_SIZE])?,
+                new_tip: Digest::deserialize(&buf[8 + DIGEST_SIZE..])?,
+            })
+        }
+    }
+}
+
+/// Rewrites every block read by ```reader``` into a new chain at ```path``` whose data sections
+/// are ```size``` bytes long. ```f``` is called with the index and data section of each old
+/// block and fills in the zeroed data section of the new block. The links of the new chain are
+/// recomputed, while the links of the old chain are verified as it is read, returning
+/// Err(Error::InvalidBlockHash(index)) if one is broken. The mapping from the old tip to the new
+/// one is written to ```Migration::path(path)``` and returned. If the rewrite fails after the new
+/// chain was created, the partially written chain is removed.
+pub fn rewrite<F>(
+    reader: &mut Reader,
+    path: &Path,
+    size: usize,
+    options: Options,
+    mut f: F,
+) -> Result<Migration>
+where
+    F: FnMut(u64, &[u8], &mut [u8]) -> Result<()>,
+{
+    let block_count: u64 = reader.block_count()?;
+    let mut block: Vec<u8> = vec![0; reader.block_size()];
+    let mut old: Vec<u8> = vec![0; reader.data_size()];
+    let mut new: Vec<u8> = vec![0; size];
+    reader.rewind()?;
+    reader.read_block(&mut block)?;
+    reader.decode_data(&block, &mut old)?;
+    let old_tip: Digest = reader.block_hash(&block)?;
+    f(0, &old, &mut new)?;
+    let mut file: File = File::create_new_with(path, &mut RawData(&new), size, options)?;
+    match append_blocks(reader, &mut file, block_count, old_tip, &mut f) {
+        Ok(migration) => {
+            fs::write(Migration::path(path), migration.to_bytes()?)?;
+            Ok(migration)
+        }
+        Err(e) => {
+            // leave nothing behind, so that the rewrite can be retried once the cause is fixed
+            drop(file);
+            fs::remove_file(path)?;
+            Err(e)
+        }
+    }
+}
+
+/// Appends the rewritten blocks after the genesis block to ```file```, verifying the links of the
+/// old chain, and returns the mapping from the old tip to the new one.
+fn append_blocks<F>(
+    reader: &mut Reader,
+    file: &mut File,
+    block_count: u64,
+    mut old_tip: Digest,
+    f: &mut F,
+) -> Result<Migration>
+where
+    F: FnMut(u64, &[u8], &mut [u8]) -> Result<()>,
+{
+    let mut block: Vec<u8> = vec![0; reader.block_size()];
+    let mut old: Vec<u8> = vec![0; reader.data_size()];
+    let mut new: Vec<u8> = vec![0; file.data_size()];
+    let mut writer: Writer = Writer::new(file)?;
+    for index in 1..block_count {
+        reader.read_block(&mut block)?;
+        if Digest::deserialize(&block[0..DIGEST_SIZE])? != old_tip {
+            return Err(Error::InvalidBlockHash(index));
+        }
+        reader.decode_data(&block, &mut old)?;
+        old_tip = reader.block_hash(&block)?;
+        new.fill(0);
+        f(index, &old, &mut new)?;
+        writer.append(&mut new)?;
+    }
+    Ok(Migration {
+        block_count,
+        old_tip,
+        new_tip: writer.last_hash().clone(),
+    })
+}
@@ -0,0 +1,244 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+use crate::io::{Error, File, Reader, Result, Writer};
+use bc_hash::sha256::{Digest, DIGEST_SIZE};
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread;
+
+/// The bytes that start every request, identifying the protocol and its version.
+const MAGIC: [u8; 4] = *b"BCR1";
+
+/// The size of a request: the magic bytes, the replica's block count, and the hash of its last block.
+const REQUEST_SIZE: usize = 4 + 8 + DIGEST_SIZE;
+
+/// The size of a response: the status, the primary's block size, and the index of a block.
+const RESPONSE_SIZE: usize = 1 + 4 + 8;
+
+/// The status of a response after which the primary streams the blocks that the replica is missing.
+const STATUS_OK: u8 = 0;
+
+/// The status of a response when the replica's chain is not a prefix of the primary's.
+const STATUS_DIVERGED: u8 = 1;
+
+/// Serves the blockchain file at ```path``` to replicas over TCP. A replica sends its block count
+/// and the hash of its last block. If that block matches the primary's block at the same index,
+/// the primary sends every block after it and then keeps sending new blocks as they are appended.
+/// Otherwise the chains have diverged and the connection is closed.
+#[derive(Debug)]
+pub struct Server {
+    path: PathBuf,
+    listener: TcpListener,
+}
+
+impl Server {
+    /// Creates a new server for the blockchain file at ```path``` listening on ```addr```.
+    pub fn bind<A: ToSocketAddrs>(path: &Path, addr: A) -> Result<Server> {
+        Ok(Self {
+            path: path.to_path_buf(),
+            listener: TcpListener::bind(addr)?,
+        })
+    }
+
+    /// Returns the address that the server is listening on.
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        self.listener.local_addr().map_err(Error::from)
+    }
+
+    /// Accepts replicas until accepting a connection fails, serving each one on its own thread.
+    /// When serving a replica ends with an error, such as when it disconnects, ```on_error``` is
+    /// called with the replica's address and the error. Returns the error that stopped the server.
+    pub fn serve<F>(&self, on_error: F) -> Result<()>
+    where
+        F: Fn(SocketAddr, Error) + Send + Sync + 'static,
+    {
+        let on_error: Arc<F> = Arc::new(on_error);
+        loop {
+            let (stream, peer) = self.listener.accept()?;
+            let path: PathBuf = self.path.clone();
+            let on_error: Arc<F> = on_error.clone();
+            thread::spawn(move || {
+                if let Err(e) = serve_replica(&path, stream) {
+                    on_error(peer, e);
+                }
+            });
+        }
+    }
+
+    /// Accepts a single replica and serves it on the current thread until it disconnects.
+    pub fn serve_one(&self) -> Result<()> {
+        let (stream, _) = self.listener.accept()?;
+        serve_replica(&self.path, stream)
+    }
+}
+
+/// Serves the blockchain file at ```path``` to the replica connected to ```stream```.
+fn serve_replica(path: &Path, mut stream: TcpStream) -> Result<()> {
+    let mut request: [u8; REQUEST_SIZE] = [0; REQUEST_SIZE];
+    stream.read_exact(&mut request)?;
+    if request[0..4] != MAGIC {
+        return Err(Error::InvalidMessage);
+    }
+    let count: u64 = u64::from_le_bytes(request[4..12].try_into().unwrap());
+    let tip: Digest = Digest::deserialize(&request[12..])?;
+
+    // the primary may be part way through appending a block, so only complete blocks are counted
+    let mut file: File = File::open_growing(path)?;
+    let mut reader: Reader = Reader::new(&mut file);
+    let primary_count: u64 = reader.stream_size()? / reader.block_size() as u64;
+    let diverged: Option<u64> = if count == 0 {
+        Some(0)
+    } else if count > primary_count {
+        Some(primary_count)
+    } else {
+        let mut block: Vec<u8> = vec![0; reader.block_size()];
+        reader.read_block_at(count - 1, &mut block)?;
+        (reader.block_hash(&block)? != tip).then_some(count - 1)
+    };
+
+    let mut response: [u8; RESPONSE_SIZE] = [0; RESPONSE_SIZE];
+    response[0] = if diverged.is_some() {
+        STATUS_DIVERGED
+    } else {
+        STATUS_OK
+    };
+    response[1..5].copy_from_slice(&(reader.block_size() as u32).to_le_bytes());
+    response[5..].copy_from_slice(&diverged.unwrap_or(primary_count).to_le_bytes());
+    stream.write_all(&response)?;
+    if let Some(index) = diverged {
+        return Err(Error::ChainsDiverged(index));
+    }
+
+    stream.set_nodelay(true)?;
+    for block in reader.follow_from(count) {
+        let (_, block) = block?;
+        stream.write_all(&block)?;
+    }
+    Ok(())
+}
+
+/// A replica of a blockchain served by a ```Server```. Iterating over it receives the blocks that
+/// are missing from the local file, validates that each one links to the last block, and appends
+/// it with a ```Writer```. Each item is the index of the appended block. Once the replica has
+/// caught up, it waits for the primary to send new blocks. The iteration ends after the first
+/// error, such as the primary closing the connection.
+#[derive(Debug)]
+pub struct Replica<'a> {
+    writer: Writer<'a>,
+    stream: TcpStream,
+    primary_count: u64,
+    buf: Vec<u8>,
+    ended: bool,
+}
+
+impl<'a> Replica<'a> {
+    /// Connects to the server at ```addr``` and requests the blocks that are missing from ```file```.
+    /// Returns Err(Error::ChainsDiverged(index)) if block ```index``` of ```file``` does not match
+    /// the primary, or Err(Error::BlockSizeMismatch(primary, replica)) if the block sizes differ.
+    /// If the blockchain is signed, the authorized keys must be set on ```file```.
+    pub fn connect<A: ToSocketAddrs>(addr: A, file: &'a mut File) -> Result<Replica<'a>> {
+        let count: u64 = file.block_count()?;
+        let block_size: usize = file.block_size();
+        let writer: Writer = Writer::new(file)?;
+        let mut stream: TcpStream = TcpStream::connect(addr)?;
+
+        let mut request: [u8; REQUEST_SIZE] = [0; REQUEST_SIZE];
+        request[0..4].copy_from_slice(&MAGIC);
+        request[4..12].copy_from_slice(&count.to_le_bytes());
+        writer.last_hash().serialize(&mut request[12..])?;
+        stream.write_all(&request)?;
+
+        let mut response: [u8; RESPONSE_SIZE] = [0; RESPONSE_SIZE];
+        stream.read_exact(&mut response)?;
+        let primary_block_size: u32 = u32::from_le_bytes(response[1..5].try_into().unwrap());
+        let index: u64 = u64::from_le_bytes(response[5..].try_into().unwrap());
+        match response[0] {
+            STATUS_OK if primary_block_size as usize == block_size => Ok(Self {
+                writer,
+                stream,
+                primary_count: index,
+                buf: vec![0; block_size],
+                ended: false,
+            }),
+            STATUS_OK => Err(Error::BlockSizeMismatch(
+                primary_block_size as usize,
+                block_size,
+            )),
+            STATUS_DIVERGED => Err(Error::ChainsDiverged(index)),
+            _ => Err(Error::InvalidMessage),
+        }
+    }
+
+    /// Returns the number of blocks the primary had when the replica connected.
+    #[inline]
+    pub fn primary_count(&self) -> u64 {
+        self.primary_count
+    }
+
+    /// Returns the total number of blocks in the local file.
+    #[inline]
+    pub fn block_count(&self) -> Result<u64> {
+        self.writer.block_count()
+    }
+
+    /// Returns the hash of the last block in the local file.
+    #[inline]
+    pub fn last_hash(&self) -> &Digest {
+        self.writer.last_hash()
+    }
+
+    /// Receives the next block from the primary and appends it to the local file, waiting until
+    /// the primary sends one. Returns the index of the appended block, or
+    /// Err(Error::InvalidBlockHash(index)) if it does not link to the last block.
+    pub fn receive(&mut self) -> Result<u64> {
+        self.stream.read_exact(&mut self.buf)?;
+        let index: u64 = self.writer.block_count()?;
+        self.writer.append_block(&self.buf)?;
+        Ok(index)
+    }
+
+    /// Receives and appends blocks until the local file has as many blocks as the primary had
+    /// when the replica connected. Returns the number of blocks that were appended.
+    pub fn catch_up(&mut self) -> Result<u64> {
+        let start: u64 = self.block_count()?;
+        while self.block_count()? < self.primary_count {
+            self.receive()?;
+        }
+        Ok(self.block_count()? - start)
+    }
+}
+
+impl Iterator for Replica<'_> {
+    type Item = Result<u64>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.ended {
+            return None;
+        }
+        let result: Result<u64> = self.receive();
+        self.ended = result.is_err();
+        Some(result)
+    }
+}
@@ -0,0 +1,135 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+use crate::io::{Deserialize, Reader, Result};
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+
+/// The number of bytes read at a time, so that reading backwards still reads the file forwards
+/// in large chunks instead of seeking back before every block.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// An iterator over the blocks of a stream from the newest towards the oldest. Each item is the
+/// index of a block together with the entire block.
+#[derive(Debug)]
+pub struct BlocksRev<'r, 'a> {
+    reader: &'r mut Reader<'a>,
+    start: u64,
+    end: u64,
+    chunk: VecDeque<Vec<u8>>,
+}
+
+impl<'r, 'a> BlocksRev<'r, 'a> {
+    /// Returns an iterator over the blocks located at ```start``` up to but not including ```end```,
+    /// starting at ```end - 1```.
+    pub(crate) fn new(reader: &'r mut Reader<'a>, start: u64, end: u64) -> Self {
+        Self {
+            reader,
+            start,
+            end,
+            chunk: VecDeque::new(),
+        }
+    }
+
+    /// Returns the reader that blocks are read from.
+    #[inline]
+    pub fn reader(&self) -> &Reader<'a> {
+        self.reader
+    }
+
+    /// Reads the chunk of blocks ending at ```end``` into ```chunk```.
+    fn read_chunk(&mut self) -> Result<()> {
+        let block_size: usize = self.reader.block_size();
+        let count: u64 = (CHUNK_SIZE / block_size).max(1) as u64;
+        let first: u64 = self.end.saturating_sub(count).max(self.start);
+        self.reader.seek(first)?;
+        for _ in first..self.end {
+            let mut buf: Vec<u8> = vec![0; block_size];
+            self.reader.read_block(&mut buf)?;
+            self.chunk.push_back(buf);
+        }
+        Ok(())
+    }
+}
+
+impl Iterator for BlocksRev<'_, '_> {
+    type Item = Result<(u64, Vec<u8>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.end <= self.start {
+            return None;
+        } else if self.chunk.is_empty() {
+            if let Err(e) = self.read_chunk() {
+                self.end = self.start;
+                return Some(Err(e));
+            }
+        }
+        self.end -= 1;
+        self.chunk.pop_back().map(|buf| Ok((self.end, buf)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len: usize = (self.end - self.start) as usize;
+        (len, Some(len))
+    }
+}
+
+/// An iterator over the data sections of the blocks of a stream from the newest towards the
+/// oldest, each deserialized as a ```T```. Each item is the index of a block together with its data.
+#[derive(Debug)]
+pub struct DataRev<'r, 'a, T> {
+    blocks: BlocksRev<'r, 'a>,
+    data: Vec<u8>,
+    phantom: PhantomData<T>,
+}
+
+impl<'r, 'a, T: Deserialize> DataRev<'r, 'a, T> {
+    pub(crate) fn new(blocks: BlocksRev<'r, 'a>) -> Self {
+        let data: Vec<u8> = vec![0; blocks.reader().data_size()];
+        Self {
+            blocks,
+            data,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: Deserialize> Iterator for DataRev<'_, '_, T> {
+    type Item = Result<(u64, T)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (index, block) = match self.blocks.next()? {
+            Ok(item) => item,
+            Err(e) => return Some(Err(e)),
+        };
+        let result: Result<T> = self
+            .blocks
+            .reader()
+            .decode_data(&block, &mut self.data)
+            .and_then(|_| T::deserialize(&self.data));
+        Some(result.map(|obj| (index, obj)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.blocks.size_hint()
+    }
+}
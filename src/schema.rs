@@ -0,0 +1,145 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+use crate::io::{Deserialize, Error, Reader, Result, Serialize};
+use std::collections::BTreeMap;
+
+/// The size of the schema version tag at the start of a versioned data section.
+pub const VERSION_SIZE: usize = 2;
+
+/// Returns the schema version tag at the start of the data section ```data```. Nothing marks a
+/// data section as versioned, so for a block that was not written with ```Versioned``` this
+/// returns the first two bytes of its record.
+pub fn version_of(data: &[u8]) -> Result<u16> {
+    if data.len() < VERSION_SIZE {
+        Err(Error::InvalidSliceLength)
+    } else {
+        Ok(u16::from_le_bytes(
+            data[0..VERSION_SIZE].try_into().unwrap(),
+        ))
+    }
+}
+
+/// A record that is serialized after a schema version tag. The data section of the block must
+/// be ```VERSION_SIZE``` bytes larger than the serialized record. The tag is a prefix inside the
+/// data section, not a field of the file header, so a reader that does not expect it, such as
+/// one written for an older chain, can not tell a versioned data section from an unversioned one.
+/// A chain should be versioned from its genisis block or not at all.
+#[derive(Debug)]
+pub struct Versioned<'a, T> {
+    pub version: u16,
+    pub record: &'a T,
+}
+
+impl<'a, T: Serialize> Versioned<'a, T> {
+    /// Creates and returns a new ```Versioned``` that tags ```record``` with ```version```.
+    pub fn new(version: u16, record: &'a T) -> Self {
+        Self { version, record }
+    }
+}
+
+impl<T: Serialize> Serialize for Versioned<'_, T> {
+    fn serialize(&self, buf: &mut [u8]) -> Result<()> {
+        if buf.len() < VERSION_SIZE {
+            Err(Error::InvalidSliceLength)
+        } else {
+            buf[0..VERSION_SIZE].copy_from_slice(&self.version.to_le_bytes());
+            self.record.serialize(&mut buf[VERSION_SIZE..])
+        }
+    }
+}
+
+/// A function that decodes the record that follows a version tag and migrates it to a ```T```.
+type Decoder<T> = Box<dyn Fn(&[u8]) -> Result<T>>;
+
+/// A set of ```Deserialize``` implementations keyed by schema version, which decodes the data
+/// section of any block, whatever version of the record layout it was written with, into the
+/// latest version ```T```.
+pub struct Registry<T> {
+    decoders: BTreeMap<u16, Decoder<T>>,
+}
+
+impl<T> std::fmt::Debug for Registry<T> {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.debug_struct("Registry")
+            .field("versions", &self.versions())
+            .finish()
+    }
+}
+
+impl<T> Default for Registry<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Registry<T> {
+    /// Creates and returns a new, empty registry.
+    pub fn new() -> Self {
+        Self {
+            decoders: BTreeMap::new(),
+        }
+    }
+
+    /// Registers ```D``` as the record layout of ```version```. Records of that version are
+    /// migrated to a ```T``` with ```D```'s implementation of ```Into<T>```.
+    pub fn register<D>(&mut self, version: u16) -> &mut Self
+    where
+        D: Deserialize + Into<T> + 'static,
+    {
+        self.register_with(version, |record: D| Ok(record.into()))
+    }
+
+    /// Registers ```D``` as the record layout of ```version```. Records of that version are
+    /// migrated to a ```T``` by ```migrate```.
+    pub fn register_with<D, F>(&mut self, version: u16, migrate: F) -> &mut Self
+    where
+        D: Deserialize + 'static,
+        F: Fn(D) -> Result<T> + 'static,
+    {
+        let decoder: Decoder<T> = Box::new(move |buf: &[u8]| migrate(D::deserialize(buf)?));
+        self.decoders.insert(version, decoder);
+        self
+    }
+
+    /// Returns the versions that have been registered, in ascending order.
+    pub fn versions(&self) -> Vec<u16> {
+        self.decoders.keys().copied().collect()
+    }
+
+    /// Decodes the versioned data section ```data``` with the implementation registered for its
+    /// version tag. Returns Err(Error::UnknownVersion(v)) if version ```v``` is not registered.
+    pub fn decode(&self, data: &[u8]) -> Result<T> {
+        let version: u16 = version_of(data)?;
+        match self.decoders.get(&version) {
+            Some(decoder) => decoder(&data[VERSION_SIZE..]),
+            None => Err(Error::UnknownVersion(version)),
+        }
+    }
+
+    /// Reads and decodes the data section of the block located at ```index```.
+    pub fn read_at(&self, reader: &mut Reader, index: u64) -> Result<T> {
+        let mut data: Vec<u8> = vec![0; reader.data_size()];
+        reader.read_data_at(index, &mut data)?;
+        self.decode(&data)
+    }
+}
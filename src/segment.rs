@@ -0,0 +1,453 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+#[cfg(feature = "encryption")]
+use crate::encryption::EncryptionKey;
+use crate::io::{Error, File, Options, Reader, Result, Serialize, Writer};
+#[cfg(feature = "signing")]
+use crate::signing::{SigningKey, VerifyingKey};
+use bc_hash::sha256::{Digest, DIGEST_SIZE};
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The size of a checkpoint in bytes.
+const CHECKPOINT_SIZE: usize = 8 + 8 + DIGEST_SIZE + DIGEST_SIZE;
+
+/// When a ```SegmentedChain``` starts a new segment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rollover {
+    /// Start a new segment once the last one holds this many blocks.
+    Blocks(u64),
+    /// Start a new segment once another block would make the last one larger than this many bytes.
+    /// Every segment holds at least one block.
+    Bytes(u64),
+}
+
+/// A compact record of the segments that have been pruned from the start of a ```SegmentedChain```,
+/// which lets validation and appends continue on the segments that remain.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Checkpoint {
+    /// The number of the first segment that was not pruned.
+    pub segment: usize,
+    /// The index of the first block that was not pruned, which is the number of blocks pruned.
+    pub index: u64,
+    /// The hash of the last block that was pruned.
+    pub hash: Digest,
+    /// The header of the chain, which was stored in the genisis block.
+    header: [u8; DIGEST_SIZE],
+}
+
+impl Checkpoint {
+    /// Transmutates the checkpoint into a vector of bytes.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut buf: Vec<u8> = vec![0; CHECKPOINT_SIZE];
+        buf[0..8].copy_from_slice(&(self.segment as u64).to_le_bytes());
+        buf[8..16].copy_from_slice(&self.index.to_le_bytes());
+        self.hash.serialize(&mut buf[16..16 + DIGEST_SIZE])?;
+        buf[16 + DIGEST_SIZE..].copy_from_slice(&self.header);
+        Ok(buf)
+    }
+
+    /// Transmutates a slice of bytes created by ```to_bytes()``` back into a checkpoint.
+    pub fn from_bytes(buf: &[u8]) -> Result<Self> {
+        if buf.len() != CHECKPOINT_SIZE {
+            Err(Error::InvalidSliceLength)
+        } else {
+            Ok(Self {
+                segment: u64::from_le_bytes(buf[0..8].try_into().unwrap()) as usize,
+                index: u64::from_le_bytes(buf[8..16].try_into().unwrap()),
+                hash: Digest::deserialize(&buf[16..16 + DIGEST_SIZE])?,
+                header: buf[16 + DIGEST_SIZE..].try_into().unwrap(),
+            })
+        }
+    }
+}
+
+/// A single file of a ```SegmentedChain``` and the index of its first block in the chain.
+#[derive(Debug)]
+struct Segment {
+    file: File,
+    first: u64,
+}
+
+/// A blockchain split across multiple files named ```<base>.000001.blk```, ```<base>.000002.blk```,
+/// and so on. Only the first segment has a header. The first block of every other segment links
+/// to the last block of the segment before it, so the segments form a single chain whose blocks
+/// are indexed from 0 across all of them. Segments can be pruned from the start of the chain, in
+/// which case a checkpoint is kept in ```<base>.checkpoint```. The methods of the chain take
+/// block indexes across all segments, while a ```Reader``` from ```reader()``` is limited to a
+/// single segment.
+#[derive(Debug)]
+pub struct SegmentedChain {
+    base: PathBuf,
+    rollover: Rollover,
+    segments: Vec<Segment>,
+    last_hash: Digest,
+    checkpoint: Option<Checkpoint>,
+}
+
+/// Returns ```error``` with the block index it refers to offset by ```first```.
+fn offset_error(error: Error, first: u64) -> Error {
+    match error {
+        Error::InvalidBlockHash(n) => Error::InvalidBlockHash(n + first),
+        Error::InvalidChecksum(n) => Error::InvalidChecksum(n + first),
+        Error::InvalidSignature(n) => Error::InvalidSignature(n + first),
+        e => e,
+    }
+}
+
+impl SegmentedChain {
+    /// Returns the path of segment ```number``` of the chain at ```base```. Segments are numbered from 1.
+    pub fn segment_path(base: &Path, number: usize) -> PathBuf {
+        let mut path: OsString = base.as_os_str().to_os_string();
+        path.push(format!(".{:06}.blk", number));
+        PathBuf::from(path)
+    }
+
+    /// Returns the path of the checkpoint of the chain at ```base```.
+    pub fn checkpoint_path(base: &Path) -> PathBuf {
+        let mut path: OsString = base.as_os_str().to_os_string();
+        path.push(".checkpoint");
+        PathBuf::from(path)
+    }
+
+    /// Creates a new segmented chain at ```base``` whose genisis block contains ```data```. See
+    /// ```File::create_new_with()``` for the meaning of ```size``` and ```options```.
+    pub fn create_new<T: Serialize>(
+        base: &Path,
+        data: &mut T,
+        size: usize,
+        options: Options,
+        rollover: Rollover,
+    ) -> Result<SegmentedChain> {
+        let mut file: File =
+            File::create_new_with(&Self::segment_path(base, 1), data, size, options)?;
+        let last_hash: Digest = Writer::new(&mut file)?.last_hash().clone();
+        Ok(Self {
+            base: base.to_path_buf(),
+            rollover,
+            segments: vec![Segment { file, first: 0 }],
+            last_hash,
+            checkpoint: None,
+        })
+    }
+
+    /// Opens every segment of an existing segmented chain at ```base```, starting after the
+    /// checkpoint if segments have been pruned. An empty last segment, which is left behind if
+    /// the process stops while starting a new segment, is removed.
+    pub fn open_existing(base: &Path, rollover: Rollover) -> Result<SegmentedChain> {
+        let checkpoint_path: PathBuf = Self::checkpoint_path(base);
+        let checkpoint: Option<Checkpoint> = if checkpoint_path.exists() {
+            Some(Checkpoint::from_bytes(&fs::read(&checkpoint_path)?)?)
+        } else {
+            None
+        };
+        let (number, mut first) = match &checkpoint {
+            Some(checkpoint) => (checkpoint.segment, checkpoint.index),
+            None => (1, 0),
+        };
+        let mut file: File = match &checkpoint {
+            Some(checkpoint) => {
+                File::open_pruned(&Self::segment_path(base, number), &checkpoint.header)?
+            }
+            None => File::open_existing(&Self::segment_path(base, number))?,
+        };
+        let mut last_hash: Digest = Writer::new(&mut file)
+            .map_err(|e| offset_error(e, first))?
+            .last_hash()
+            .clone();
+        let mut segments: Vec<Segment> = vec![Segment { file, first }];
+        first += segments[0].file.block_count()?;
+        loop {
+            let path: PathBuf = Self::segment_path(base, number + segments.len());
+            if !path.exists() {
+                break;
+            }
+            let mut file: File = match segments[0].file.open_segment(&path) {
+                Err(Error::FileIsEmpty)
+                    if !Self::segment_path(base, number + segments.len() + 1).exists() =>
+                {
+                    std::fs::remove_file(&path)?;
+                    break;
+                }
+                result => result?,
+            };
+            let count: u64 = file.block_count()?;
+            last_hash = Writer::new(&mut file)
+                .map_err(|e| offset_error(e, first))?
+                .last_hash()
+                .clone();
+            segments.push(Segment { file, first });
+            first += count;
+        }
+        Ok(Self {
+            base: base.to_path_buf(),
+            rollover,
+            segments,
+            last_hash,
+            checkpoint,
+        })
+    }
+
+    /// Returns the block size of the chain in bytes.
+    #[inline]
+    pub fn block_size(&self) -> usize {
+        self.segments[0].file.block_size()
+    }
+
+    /// Returns the size of the data section of each block in bytes.
+    #[inline]
+    pub fn data_size(&self) -> usize {
+        self.segments[0].file.data_size()
+    }
+
+    /// Returns the number of segments in the chain.
+    #[inline]
+    pub fn segment_count(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// Returns the path of the base of the chain, to which segment numbers are appended.
+    #[inline]
+    pub fn base(&self) -> &Path {
+        &self.base
+    }
+
+    /// Returns the hash of the last block in the chain.
+    #[inline]
+    pub fn last_hash(&self) -> &Digest {
+        &self.last_hash
+    }
+
+    /// Returns the checkpoint of the pruned segments, or None if no segments have been pruned.
+    #[inline]
+    pub fn checkpoint(&self) -> Option<&Checkpoint> {
+        self.checkpoint.as_ref()
+    }
+
+    /// Returns the number of the first segment that has not been pruned.
+    #[inline]
+    fn first_segment(&self) -> usize {
+        self.checkpoint.as_ref().map_or(1, |c| c.segment)
+    }
+
+    /// Returns the index of the first block that has not been pruned.
+    #[inline]
+    pub fn first_index(&self) -> u64 {
+        self.segments[0].first
+    }
+
+    /// Returns the total number of blocks in all segments, including any that have been pruned.
+    pub fn block_count(&self) -> Result<u64> {
+        let last: &Segment = self.segments.last().unwrap();
+        Ok(last.first + last.file.block_count()?)
+    }
+
+    /// Returns a reader over the segment that contains the block located at ```index```,
+    /// along with the index of that block within the segment. Returns
+    /// Err(Error::BlockPruned(index)) if the segment has been pruned.
+    ///
+    /// The reader only sees that one segment: its block indexes, ```block_count()```, iterators,
+    /// and validation are all relative to the segment, and the links to the segments around it
+    /// are not checked. It must not be used for operations on the whole chain; use
+    /// ```block_count()```, ```read_block_at()```, ```read_data_at()```, and
+    /// ```validate_all_blocks()``` on the ```SegmentedChain``` instead, which work across segments
+    /// with chain indexes.
+    pub fn reader(&mut self, index: u64) -> Result<(Reader<'_>, u64)> {
+        if index >= self.block_count()? {
+            return Err(Error::BlockNumDoesNotExist);
+        } else if index < self.first_index() {
+            return Err(Error::BlockPruned(index));
+        }
+        let s: usize = self.segments.partition_point(|s| s.first <= index) - 1;
+        let segment: &mut Segment = &mut self.segments[s];
+        Ok((Reader::new(&mut segment.file), index - segment.first))
+    }
+
+    /// Reads the entire block located at ```index``` and copies it into ```buf```.
+    /// The length of ```buf``` must be exactly equal to the total block size.
+    pub fn read_block_at(&mut self, index: u64, buf: &mut [u8]) -> Result<()> {
+        let (mut reader, i) = self.reader(index)?;
+        reader
+            .read_block_at(i, buf)
+            .map_err(|e| offset_error(e, index - i))
+    }
+
+    /// Reads the data section of the block located at ```index``` and copies it into ```buf```.
+    /// The length of ```buf``` must be exactly equal to ```data_size()```.
+    pub fn read_data_at(&mut self, index: u64, buf: &mut [u8]) -> Result<()> {
+        let (mut reader, i) = self.reader(index)?;
+        reader
+            .read_data_at(i, buf)
+            .map_err(|e| offset_error(e, index - i))
+    }
+
+    /// Returns true if the last segment is full and the next block belongs in a new segment.
+    fn is_full(&self) -> Result<bool> {
+        let file: &File = &self.segments.last().unwrap().file;
+        Ok(match self.rollover {
+            Rollover::Blocks(n) => file.block_count()? >= n,
+            Rollover::Bytes(n) => file.size()? + file.block_size() as u64 > n,
+        })
+    }
+
+    /// Writes a new block to the end of the chain, first starting a new segment if the last one
+    /// is full. The length of ```data``` must be exactly equal to ```data_size()```.
+    pub fn append(&mut self, data: &mut [u8]) -> Result<()> {
+        if self.is_full()? {
+            let first: u64 = self.block_count()?;
+            let path: PathBuf =
+                Self::segment_path(&self.base, self.first_segment() + self.segments.len());
+            let mut file: File = self.segments[0].file.create_segment(&path)?;
+            let mut writer: Writer = Writer::continue_from(&mut file, self.last_hash.clone());
+            let result: Result<Digest> = writer.append(data).map(|_| writer.last_hash().clone());
+            drop(writer);
+            match result {
+                Ok(last_hash) => self.last_hash = last_hash,
+                Err(e) => {
+                    std::fs::remove_file(&path)?;
+                    return Err(e);
+                }
+            }
+            self.segments.push(Segment { file, first });
+        } else {
+            let segment: &mut Segment = self.segments.last_mut().unwrap();
+            let mut writer: Writer =
+                Writer::continue_from(&mut segment.file, self.last_hash.clone());
+            writer.append(data)?;
+            self.last_hash = writer.last_hash().clone();
+        }
+        Ok(())
+    }
+
+    /// Validates every segment with ```Reader::validate_all_blocks()``` and verifies that the first
+    /// block of each segment links to the last block of the segment before it, or to the hash in
+    /// the checkpoint if earlier segments have been pruned. Returns
+    /// Err(Error::InvalidBlockHash(b)) if block ```b``` does not link to the block before it.
+    pub fn validate_all_blocks(&mut self) -> Result<()> {
+        let block_size: usize = self.block_size();
+        let mut buf: Vec<u8> = vec![0; block_size];
+        let mut prev_hash: Option<Digest> = self.checkpoint.as_ref().map(|c| c.hash.clone());
+        for segment in self.segments.iter_mut() {
+            let first: u64 = segment.first;
+            let mut reader: Reader = Reader::new(&mut segment.file);
+            reader
+                .validate_all_blocks()
+                .map_err(|e| offset_error(e, first))?;
+            if let Some(prev_hash) = prev_hash {
+                reader
+                    .read_block_at(0, &mut buf)
+                    .map_err(|e| offset_error(e, first))?;
+                if Digest::deserialize(&buf[0..DIGEST_SIZE])? != prev_hash {
+                    return Err(Error::InvalidBlockHash(first));
+                }
+            }
+            let last: u64 = reader.block_count()? - 1;
+            reader
+                .read_block_at(last, &mut buf)
+                .map_err(|e| offset_error(e, first))?;
+            prev_hash = Some(reader.block_hash(&buf)?);
+        }
+        Ok(())
+    }
+
+    /// Removes the first ```count``` remaining segments and replaces them with a checkpoint. At least
+    /// one segment always remains. Returns the new checkpoint.
+    pub fn prune(&mut self, count: usize) -> Result<Checkpoint> {
+        self.remove_segments(count, None)
+    }
+
+    /// Same as ```prune()```, but moves the segments into the directory ```dir``` rather than
+    /// deleting them.
+    pub fn archive(&mut self, count: usize, dir: &Path) -> Result<Checkpoint> {
+        self.remove_segments(count, Some(dir))
+    }
+
+    /// Writes a checkpoint after the first ```count``` remaining segments, then moves them into
+    /// ```dir``` or deletes them. The checkpoint is written first, so that a failure part way
+    /// through leaves segments that can be removed again rather than a chain that cannot be opened.
+    fn remove_segments(&mut self, count: usize, dir: Option<&Path>) -> Result<Checkpoint> {
+        if count == 0 || count >= self.segments.len() {
+            return Err(Error::BlockNumDoesNotExist);
+        }
+        let first_segment: usize = self.first_segment();
+        let mut header: [u8; DIGEST_SIZE] = [0; DIGEST_SIZE];
+        self.segments[0].file.write_header(&mut header);
+        let mut buf: Vec<u8> = vec![0; self.block_size()];
+        let index: u64 = self.segments[count].first;
+        self.read_block_at(index - 1, &mut buf)?;
+        let hash: Digest = Reader::new(&mut self.segments[0].file).block_hash(&buf)?;
+        let checkpoint: Checkpoint = Checkpoint {
+            segment: first_segment + count,
+            index,
+            hash,
+            header,
+        };
+        let path: PathBuf = Self::checkpoint_path(&self.base);
+        let mut tmp: OsString = path.as_os_str().to_os_string();
+        tmp.push(".tmp");
+        fs::write(&tmp, checkpoint.to_bytes()?)?;
+        fs::rename(&tmp, &path)?;
+        for (n, segment) in self.segments.drain(0..count).enumerate() {
+            let path: PathBuf = Self::segment_path(&self.base, first_segment + n);
+            drop(segment);
+            match dir {
+                Some(dir) => {
+                    let to: PathBuf = dir.join(path.file_name().unwrap());
+                    if fs::rename(&path, &to).is_err() {
+                        // the archive may be on another file system
+                        fs::copy(&path, &to)?;
+                        fs::remove_file(&path)?;
+                    }
+                }
+                None => fs::remove_file(&path)?,
+            }
+        }
+        self.checkpoint = Some(checkpoint.clone());
+        Ok(checkpoint)
+    }
+
+    /// Sets the key used to sign new blocks appended to a signed chain.
+    #[cfg(feature = "signing")]
+    pub fn set_signing_key(&mut self, key: SigningKey) {
+        for segment in self.segments.iter_mut() {
+            segment.file.set_signing_key(key.clone());
+        }
+    }
+
+    /// Sets the public keys whose signatures are accepted when validating a signed chain.
+    #[cfg(feature = "signing")]
+    pub fn set_authorized_keys(&mut self, keys: Vec<VerifyingKey>) {
+        for segment in self.segments.iter_mut() {
+            segment.file.set_authorized_keys(keys.clone());
+        }
+    }
+
+    /// Sets the key used to encrypt and decrypt the data sections of an encrypted chain.
+    #[cfg(feature = "encryption")]
+    pub fn set_encryption_key(&mut self, key: EncryptionKey) {
+        for segment in self.segments.iter_mut() {
+            segment.file.set_encryption_key(key);
+        }
+    }
+}
@@ -0,0 +1,82 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+use crate::io::{Deserialize, Error, Result, Serialize};
+use serde::de::DeserializeOwned;
+
+/// The size of the length that precedes a record encoded with postcard.
+pub const LENGTH_SIZE: usize = 4;
+
+/// Adapts any type that implements serde's ```Serialize``` and ```Deserialize``` traits to
+/// bc_io's. The record is encoded with postcard, preceded by its length in little endian byte
+/// order, and followed by zeros to fill the rest of the data section.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Serde<T>(pub T);
+
+impl<T> Serde<T> {
+    /// Returns the wrapped record.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+/// Encodes ```record``` into the data section ```buf```. Returns
+/// Err(Error::RecordTooLarge(size, capacity)) if the encoding, including its length, is larger
+/// than ```buf```.
+pub fn encode<T: serde::Serialize + ?Sized>(record: &T, buf: &mut [u8]) -> Result<()> {
+    let bytes: Vec<u8> = postcard::to_allocvec(record).map_err(|_| Error::InvalidEncoding)?;
+    let size: usize = LENGTH_SIZE + bytes.len();
+    if size > buf.len() || bytes.len() > u32::MAX as usize {
+        return Err(Error::RecordTooLarge(size, buf.len()));
+    }
+    buf[0..LENGTH_SIZE].copy_from_slice(&(bytes.len() as u32).to_le_bytes());
+    buf[LENGTH_SIZE..size].copy_from_slice(&bytes);
+    buf[size..].fill(0);
+    Ok(())
+}
+
+/// Decodes a record encoded by ```encode()``` from the data section ```buf```.
+pub fn decode<T: DeserializeOwned>(buf: &[u8]) -> Result<T> {
+    if buf.len() < LENGTH_SIZE {
+        return Err(Error::InvalidSliceLength);
+    }
+    let length: usize = u32::from_le_bytes(buf[0..LENGTH_SIZE].try_into().unwrap()) as usize;
+    match buf[LENGTH_SIZE..].get(0..length) {
+        Some(bytes) => postcard::from_bytes(bytes).map_err(|_| Error::InvalidEncoding),
+        None => Err(Error::InvalidEncoding),
+    }
+}
+
+impl<T: serde::Serialize> Serialize for Serde<T> {
+    fn serialize(&self, buf: &mut [u8]) -> Result<()> {
+        encode(&self.0, buf)
+    }
+}
+
+impl<T: DeserializeOwned> Deserialize for Serde<T> {
+    fn deserialize(buf: &[u8]) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        decode(buf).map(Serde)
+    }
+}
@@ -0,0 +1,186 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+use crate::io::{Error, File, Result};
+use bc_hash::sha256::{Digest, DIGEST_SIZE};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard};
+
+/// The hash of the last block along with a buffer for building the next one.
+#[derive(Debug)]
+struct Tail {
+    last_hash: Digest,
+    buf: Vec<u8>,
+}
+
+/// A blockchain file that can be shared between threads, typically in an ```Arc```. Any number of
+/// threads may read blocks at the same time using positional reads, which do not share a stream
+/// position, while appends are serialized so that there is only ever one writer. A block becomes
+/// visible to readers once it has been completely written. No other ```Writer```, in this process
+/// or another, may append to the file while it is shared. Keys must be set on the ```File```
+/// before it is shared.
+#[derive(Debug)]
+pub struct ChainFile {
+    file: File,
+    count: AtomicU64,
+    tail: Mutex<Tail>,
+}
+
+impl ChainFile {
+    /// Creates a new shared handle for ```file```, reading the hash of its last block.
+    pub fn new(file: File) -> Result<ChainFile> {
+        let count: u64 = file.block_count()?;
+        let mut buf: Vec<u8> = vec![0; file.block_size()];
+        file.pread_block(count - 1, &mut buf)?;
+        let last_hash: Digest = file.block_hash(&buf)?;
+        Ok(Self {
+            file,
+            count: AtomicU64::new(count),
+            tail: Mutex::new(Tail { last_hash, buf }),
+        })
+    }
+
+    /// Opens the existing blockchain file at ```path``` and creates a new shared handle for it.
+    pub fn open_existing(path: &Path) -> Result<ChainFile> {
+        Self::new(File::open_existing(path)?)
+    }
+
+    /// Returns the underlying blockchain file.
+    pub fn into_inner(self) -> File {
+        self.file
+    }
+
+    /// Returns the block size of the underlying blockchain file.
+    #[inline]
+    pub fn block_size(&self) -> usize {
+        self.file.block_size()
+    }
+
+    /// Returns the size of the data section of each block in bytes.
+    #[inline]
+    pub fn data_size(&self) -> usize {
+        self.file.data_size()
+    }
+
+    /// Returns the path of the underlying blockchain file.
+    #[inline]
+    pub fn path(&self) -> &Path {
+        self.file.path()
+    }
+
+    /// Returns the number of blocks that have been completely written.
+    #[inline]
+    pub fn block_count(&self) -> u64 {
+        self.count.load(Ordering::Acquire)
+    }
+
+    /// Returns the hash of the last block.
+    pub fn last_hash(&self) -> Digest {
+        self.lock().last_hash.clone()
+    }
+
+    /// Locks the tail of the chain, ignoring poisoning since the tail is only changed once a
+    /// block has been written.
+    fn lock(&self) -> MutexGuard<'_, Tail> {
+        self.tail.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Reads the entire block located at ```index``` and copies it into ```buf```. The length
+    /// of ```buf``` must be exactly equal to the total block size. Returns
+    /// Err(Error::BlockNumDoesNotExist) if the block has not been completely written.
+    pub fn read_block_at(&self, index: u64, buf: &mut [u8]) -> Result<()> {
+        if buf.len() != self.block_size() {
+            Err(Error::InvalidSliceLength)
+        } else if index >= self.block_count() {
+            Err(Error::BlockNumDoesNotExist)
+        } else {
+            self.file.pread_block(index, buf)
+        }
+    }
+
+    /// Reads the data section of the block located at ```index``` and copies it into ```buf```.
+    /// The length of ```buf``` must be exactly equal to ```data_size()```.
+    pub fn read_data_at(&self, index: u64, buf: &mut [u8]) -> Result<()> {
+        if buf.len() != self.data_size() {
+            return Err(Error::InvalidSliceLength);
+        }
+        let mut block: Vec<u8> = vec![0; self.block_size()];
+        self.read_block_at(index, &mut block)?;
+        self.file.decode_data(&block, buf)
+    }
+
+    /// Calculates the hash of the block located at ```index - 1``` and compares it to the previous
+    /// block hash stored in the block located at ```index```, verifying checksums and signatures
+    /// the same way as ```Reader::validate_block_at()```.
+    pub fn validate_block_at(&self, index: u64) -> Result<()> {
+        let mut buf: Vec<u8> = vec![0; self.block_size()];
+        if index > 0 {
+            self.read_block_at(index - 1, &mut buf)?;
+            let prev_hash: Digest = self.file.block_hash(&buf)?;
+            self.read_block_at(index, &mut buf)?;
+            if Digest::deserialize(&buf[0..DIGEST_SIZE])? != prev_hash {
+                return Err(Error::InvalidBlockHash(index));
+            }
+        } else {
+            self.read_block_at(index, &mut buf)?;
+        }
+        self.file.check_signature(index, &buf)
+    }
+
+    /// Validates every block that has been completely written, in order.
+    pub fn validate_all_blocks(&self) -> Result<()> {
+        let count: u64 = self.block_count();
+        let mut buf: Vec<u8> = vec![0; self.block_size()];
+        let mut prev_hash: Option<Digest> = None;
+        for index in 0..count {
+            self.read_block_at(index, &mut buf)?;
+            if let Some(prev_hash) = prev_hash {
+                if Digest::deserialize(&buf[0..DIGEST_SIZE])? != prev_hash {
+                    return Err(Error::InvalidBlockHash(index));
+                }
+            }
+            self.file.check_signature(index, &buf)?;
+            prev_hash = Some(self.file.block_hash(&buf)?);
+        }
+        Ok(())
+    }
+
+    /// Appends a new block containing ```data``` to the end of the chain and returns its index.
+    /// The length of ```data``` must be exactly equal to ```data_size()```. Appends from
+    /// different threads are written one at a time and never block readers.
+    pub fn append(&self, data: &[u8]) -> Result<u64> {
+        if data.len() != self.data_size() {
+            return Err(Error::InvalidSliceLength);
+        }
+        let mut tail: MutexGuard<'_, Tail> = self.lock();
+        let tail: &mut Tail = &mut tail;
+        let index: u64 = self.block_count();
+        let hash: Digest = self
+            .file
+            .build_block(&tail.last_hash, data, &mut tail.buf)?;
+        self.file.pwrite_block(index, &tail.buf)?;
+        tail.last_hash = hash;
+        self.count.store(index + 1, Ordering::Release);
+        Ok(index)
+    }
+}
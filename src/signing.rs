@@ -0,0 +1,41 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+use ed25519_dalek::{Signature, Signer, Verifier};
+
+pub use ed25519_dalek::{SigningKey, VerifyingKey};
+
+/// Signs ```message``` with ```key``` and copies the signature into ```buf```,
+/// whose length must be exactly equal to ```SIGNATURE_SIZE```.
+pub(crate) fn sign(key: &SigningKey, message: &[u8], buf: &mut [u8]) {
+    buf.copy_from_slice(&key.sign(message).to_bytes());
+}
+
+/// Returns true if ```signature``` is a valid signature of ```message``` by any of ```keys```.
+pub(crate) fn verify(keys: &[VerifyingKey], message: &[u8], signature: &[u8]) -> bool {
+    match Signature::from_slice(signature) {
+        Ok(signature) => keys
+            .iter()
+            .any(|key| key.verify(message, &signature).is_ok()),
+        Err(_) => false,
+    }
+}
@@ -0,0 +1,142 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+use crate::io::{Error, Reader, Result};
+use crate::layout::{FieldCursor, Layout};
+use bc_hash::sha256::{Digest, DIGEST_SIZE};
+
+/// A borrowed view of an entire block, such as the buffer filled by ```Reader::read_block()```,
+/// whose fields are decoded from the data section only when they are accessed.
+#[derive(Debug, Clone, Copy)]
+pub struct BlockView<'a> {
+    block: &'a [u8],
+    data: &'a [u8],
+}
+
+/// A typed view over the data section of a block, usually a thin wrapper around a ```BlockView```
+/// with an accessor for each field. The offsets passed to the ```BlockView``` accessors are best
+/// taken from the prefixes of ```LAYOUT``` with ```Layout::offset()```, so that they stay in step
+/// with it.
+pub trait View<'a>: Sized {
+    /// The layout of the data section, which must be the same size as it.
+    const LAYOUT: Layout;
+
+    /// Wraps a view whose data section has already been verified against ```LAYOUT```.
+    fn from_view(view: BlockView<'a>) -> Self;
+}
+
+/// Defines an accessor that decodes a field at an offset into the data section.
+macro_rules! get_at {
+    ($name:ident, $get:ident, $t:ty) => {
+        #[doc = concat!("Decodes the ```", stringify!($t), "``` at ```offset``` in the data section.")]
+        pub fn $name(&self, offset: usize) -> Result<$t> {
+            self.cursor_at(offset)?.$get()
+        }
+    };
+}
+
+impl<'a> BlockView<'a> {
+    /// Creates and returns a view of ```block```, an entire block read by ```reader```. Returns
+    /// Err(Error::InvalidSliceLength) if ```block``` is not the size of a block or ```layout``` is
+    /// not the size of the data section, or Err(Error::DataNotPlain) if the data section is
+    /// compressed or encrypted and so can not be viewed in place.
+    pub fn new(reader: &Reader, block: &'a [u8], layout: &Layout) -> Result<Self> {
+        if !reader.is_plain() {
+            Err(Error::DataNotPlain)
+        } else if block.len() != reader.block_size() || layout.size() != reader.data_size() {
+            Err(Error::InvalidSliceLength)
+        } else {
+            Ok(Self {
+                block,
+                data: &block[DIGEST_SIZE..DIGEST_SIZE + layout.size()],
+            })
+        }
+    }
+
+    /// Same as ```new()```, but wraps the view in the typed view ```V```.
+    pub fn typed<V: View<'a>>(reader: &Reader, block: &'a [u8]) -> Result<V> {
+        Ok(V::from_view(Self::new(reader, block, &V::LAYOUT)?))
+    }
+
+    /// Returns the entire block.
+    #[inline]
+    pub fn block(&self) -> &'a [u8] {
+        self.block
+    }
+
+    /// Returns the data section of the block.
+    #[inline]
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Decodes the previous block hash stored in the block.
+    pub fn prev_hash(&self) -> Result<Digest> {
+        Ok(Digest::deserialize(&self.block[0..DIGEST_SIZE])?)
+    }
+
+    /// Returns a cursor over the data section positioned at ```offset```.
+    pub fn cursor_at(&self, offset: usize) -> Result<FieldCursor<&'a [u8]>> {
+        let mut cursor: FieldCursor<&'a [u8]> = FieldCursor::new(self.data);
+        cursor.skip(offset)?;
+        Ok(cursor)
+    }
+
+    /// Returns the ```size``` bytes at ```offset``` in the data section without copying them.
+    pub fn get_slice(&self, offset: usize, size: usize) -> Result<&'a [u8]> {
+        match offset.checked_add(size) {
+            Some(end) if end <= self.data.len() => Ok(&self.data[offset..end]),
+            _ => Err(Error::InvalidSliceLength),
+        }
+    }
+
+    /// Decodes the ```Digest``` at ```offset``` in the data section.
+    pub fn get_digest(&self, offset: usize) -> Result<Digest> {
+        self.cursor_at(offset)?.get_digest()
+    }
+
+    get_at!(get_u8, get_u8, u8);
+    get_at!(get_u16_le, get_u16_le, u16);
+    get_at!(get_u32_le, get_u32_le, u32);
+    get_at!(get_u64_le, get_u64_le, u64);
+    get_at!(get_i8, get_i8, i8);
+    get_at!(get_i16_le, get_i16_le, i16);
+    get_at!(get_i32_le, get_i32_le, i32);
+    get_at!(get_i64_le, get_i64_le, i64);
+}
+
+/// Reads every block from the start of the stream into a single reused buffer and passes its
+/// index and a view of it, whose data section has the layout ```layout```, to ```f```. No memory
+/// is allocated per block. Returns the number of blocks visited.
+pub fn for_each_view<F>(reader: &mut Reader, layout: &Layout, mut f: F) -> Result<u64>
+where
+    F: FnMut(u64, BlockView<'_>) -> Result<()>,
+{
+    let block_count: u64 = reader.block_count()?;
+    let mut buf: Vec<u8> = vec![0; reader.block_size()];
+    reader.rewind()?;
+    for index in 0..block_count {
+        reader.read_block(&mut buf)?;
+        f(index, BlockView::new(reader, &buf, layout)?)?;
+    }
+    Ok(block_count)
+}
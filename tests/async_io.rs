@@ -0,0 +1,94 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+mod common;
+
+use bc_io::async_io::{AsyncReader, AsyncWriter, Blocks};
+use bc_io::io::{Options, Result};
+use bc_io::shared::ChainFile;
+use futures_core::Stream;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+/// Runs ```future``` to completion on a single threaded tokio runtime.
+fn block_on<F: Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+        .block_on(future)
+}
+
+/// Returns the next item of ```blocks```.
+async fn next(blocks: &mut Blocks) -> Option<Result<(u64, Vec<u8>)>> {
+    std::future::poll_fn(|cx| Pin::new(&mut *blocks).poll_next(cx)).await
+}
+
+#[test]
+fn async_appends_are_visible_to_async_readers() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("chain.blk");
+    drop(common::create_chain(&path, 2, 16, Options::default()).unwrap());
+    block_on(async {
+        let reader: AsyncReader = AsyncReader::open_existing(&path).await.unwrap();
+        let mut writer: AsyncWriter = AsyncWriter::new(reader.chain().clone());
+        assert_eq!(writer.block_count(), 2);
+        for index in 2..5 {
+            assert_eq!(writer.append(common::data(index, 16)).await.unwrap(), index);
+        }
+        assert_eq!(reader.block_count(), 5);
+        assert_eq!(reader.read_data_at(3).await.unwrap(), common::data(3, 16));
+        reader.validate_all_blocks().await.unwrap();
+        assert!(writer.append(vec![0; 8]).await.is_err());
+
+        let mut blocks: Blocks = reader.blocks_from(1);
+        let mut index: u64 = 1;
+        while let Some(block) = next(&mut blocks).await {
+            let (i, block) = block.unwrap();
+            assert_eq!(i, index);
+            assert_eq!(&block[32..], common::data(i, 16).as_slice());
+            index += 1;
+        }
+        assert_eq!(index, 5);
+        assert!(reader.read_block_at(5).await.is_err());
+    });
+}
+
+#[test]
+fn readers_share_a_chain_with_blocking_code() {
+    let dir = tempfile::tempdir().unwrap();
+    let file =
+        common::create_chain(&dir.path().join("chain.blk"), 1, 16, Options::default()).unwrap();
+    let chain: Arc<ChainFile> = Arc::new(ChainFile::new(file).unwrap());
+    chain.append(&common::data(1, 16)).unwrap();
+    let reader: AsyncReader = AsyncReader::new(chain.clone());
+    let writer: AsyncWriter = AsyncWriter::new(chain.clone());
+    assert_eq!(block_on(writer.last_hash()).unwrap(), chain.last_hash());
+    let data: Vec<u8> = block_on(reader.read_data_at(1)).unwrap();
+    assert_eq!(data, common::data(1, 16));
+    let mut blocks: Blocks = reader.blocks();
+    assert_eq!(
+        block_on(async { next(&mut blocks).await.unwrap().unwrap().0 }),
+        0
+    );
+    assert_eq!(blocks.index(), 1);
+}
@@ -0,0 +1,141 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+mod common;
+
+use bc_io::bloom::{BloomFilter, BloomIndex};
+use bc_io::io::{Error, File, Options, Reader, Result, Writer};
+
+/// Keys each block written by ```common::data()``` by its index.
+fn key(data: &[u8]) -> Result<Vec<u8>> {
+    Ok(data[0..8].to_vec())
+}
+
+#[test]
+fn filters_never_miss_an_inserted_key() {
+    let mut filter: BloomFilter = BloomFilter::with_capacity(100, 10).unwrap();
+    assert_eq!(filter.size(), 125);
+    assert_eq!(filter.hashes(), 7);
+    for n in 0..100u32 {
+        filter.insert(&n.to_le_bytes());
+    }
+    assert!((0..100u32).all(|n| filter.contains(&n.to_le_bytes())));
+    let copy: BloomFilter = BloomFilter::from_bytes(filter.as_bytes(), filter.hashes()).unwrap();
+    assert_eq!(copy, filter);
+    filter.clear();
+    assert!(!filter.contains(&0u32.to_le_bytes()));
+    assert!(matches!(
+        BloomFilter::with_capacity(u64::MAX, 10),
+        Err(Error::IntegerOverflow)
+    ));
+    assert!(BloomFilter::from_bytes(&[], 1).is_err());
+}
+
+#[test]
+fn keys_are_found_through_their_group_filters() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("chain.blk");
+    let mut file: File = common::create_chain(&path, 10, 16, Options::default()).unwrap();
+    let mut index: BloomIndex =
+        BloomIndex::open(&mut Reader::new(&mut file), "id", 4, 10, key).unwrap();
+    assert!(BloomIndex::path(&path, "id").exists());
+    assert_eq!(index.len(), 10);
+    assert_eq!(index.group_size(), 4);
+    assert!(index.may_contain(&6u64.to_le_bytes()));
+    let mut reader: Reader = Reader::new(&mut file);
+    assert_eq!(
+        index.find(&mut reader, &6u64.to_le_bytes()).unwrap(),
+        vec![6]
+    );
+    assert!(index
+        .find(&mut reader, &99u64.to_le_bytes())
+        .unwrap()
+        .is_empty());
+    drop(index);
+
+    {
+        let mut writer: Writer = Writer::new(&mut file).unwrap();
+        writer.append(&mut common::data(10, 16)).unwrap();
+        writer.append(&mut common::data(1, 16)).unwrap();
+    }
+    let mut index: BloomIndex =
+        BloomIndex::open(&mut Reader::new(&mut file), "id", 4, 10, key).unwrap();
+    assert_eq!(index.len(), 12);
+    assert_eq!(
+        Reader::new(&mut file)
+            .find_by_bloom(&mut index, &1u64.to_le_bytes())
+            .unwrap(),
+        vec![1, 11]
+    );
+    drop(index);
+
+    let mut index: BloomIndex =
+        BloomIndex::open(&mut Reader::new(&mut file), "id", 3, 8, key).unwrap();
+    assert_eq!(index.group_size(), 3);
+    assert_eq!(
+        index
+            .find(&mut Reader::new(&mut file), &10u64.to_le_bytes())
+            .unwrap(),
+        vec![10]
+    );
+}
+
+#[test]
+fn oversized_filters_are_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut file: File =
+        common::create_chain(&dir.path().join("chain.blk"), 1, 16, Options::default()).unwrap();
+    assert!(matches!(
+        BloomIndex::open(&mut Reader::new(&mut file), "id", u64::MAX, 10, key),
+        Err(Error::IntegerOverflow)
+    ));
+}
+
+#[test]
+fn sidecars_of_a_recreated_chain_are_rebuilt() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("chain.blk");
+    let mut file: File = common::create_chain(&path, 8, 16, Options::default()).unwrap();
+    drop(BloomIndex::open(&mut Reader::new(&mut file), "id", 4, 10, key).unwrap());
+    drop(file);
+
+    // a different chain with as many blocks at the same path
+    std::fs::remove_file(&path).unwrap();
+    let mut file: File =
+        File::create_new(&path, &mut common::Raw(&common::data(100, 16)), 16).unwrap();
+    {
+        let mut writer: Writer = Writer::new(&mut file).unwrap();
+        for index in 101..108 {
+            writer.append(&mut common::data(index, 16)).unwrap();
+        }
+    }
+    let mut index: BloomIndex =
+        BloomIndex::open(&mut Reader::new(&mut file), "id", 4, 10, key).unwrap();
+    assert_eq!(index.len(), 8);
+    assert_eq!(
+        Reader::new(&mut file)
+            .find_by_bloom(&mut index, &105u64.to_le_bytes())
+            .unwrap(),
+        vec![5]
+    );
+    assert!(!index.may_contain(&5u64.to_le_bytes()));
+}
@@ -0,0 +1,70 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+use bc_io::io::{Error, File, Options, Result, Serialize, Writer};
+use std::path::Path;
+
+/// The raw data section of a block.
+#[allow(dead_code)]
+pub struct Raw<'a>(pub &'a [u8]);
+
+impl Serialize for Raw<'_> {
+    fn serialize(&self, buf: &mut [u8]) -> Result<()> {
+        if buf.len() != self.0.len() {
+            Err(Error::InvalidSliceLength)
+        } else {
+            buf.copy_from_slice(self.0);
+            Ok(())
+        }
+    }
+}
+
+/// Returns the data section of block ```index```, which is the index in little endian byte
+/// order followed by copies of its low byte. ```size``` must be at least 8.
+#[allow(dead_code)]
+pub fn data(index: u64, size: usize) -> Vec<u8> {
+    let mut buf: Vec<u8> = vec![index as u8; size];
+    buf[0..8].copy_from_slice(&index.to_le_bytes());
+    buf
+}
+
+/// Creates a chain at ```path``` with ```count``` blocks whose data sections are
+/// ```data(index, size)```.
+#[allow(dead_code)]
+pub fn create_chain(path: &Path, count: u64, size: usize, options: Options) -> Result<File> {
+    let mut file: File = File::create_new_with(path, &mut Raw(&data(0, size)), size, options)?;
+    {
+        let mut writer: Writer = Writer::new(&mut file)?;
+        for index in 1..count {
+            writer.append(&mut data(index, size))?;
+        }
+    }
+    Ok(file)
+}
+
+/// Flips every bit of the byte at ```offset``` in the file at ```path```.
+#[allow(dead_code)]
+pub fn corrupt(path: &Path, offset: usize) {
+    let mut bytes: Vec<u8> = std::fs::read(path).unwrap();
+    bytes[offset] ^= 0xff;
+    std::fs::write(path, bytes).unwrap();
+}
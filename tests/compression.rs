@@ -0,0 +1,99 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+mod common;
+
+use bc_io::compress::Compression;
+use bc_io::io::{Error, File, Options};
+#[cfg(any(feature = "lz4", feature = "zstd"))]
+use bc_io::io::{Reader, Writer};
+
+/// Writes a chain of compressible blocks with ```compression```, reopens it, and checks
+/// that every data section reads back unchanged.
+#[cfg(any(feature = "lz4", feature = "zstd"))]
+fn round_trip(compression: Compression, hash_uncompressed: bool) {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("chain.blk");
+    let options: Options = Options {
+        compression,
+        compressed_size: Some(64),
+        hash_uncompressed,
+        ..Options::default()
+    };
+    let block_size: usize = common::create_chain(&path, 4, 256, options)
+        .unwrap()
+        .block_size();
+    assert_eq!(block_size, 32 + 64);
+
+    let mut file: File = File::open_existing(&path).unwrap();
+    assert_eq!(file.compression(), compression);
+    assert_eq!(file.data_size(), 256);
+    let mut reader: Reader = Reader::new(&mut file);
+    reader.validate_all_blocks().unwrap();
+    let mut data: Vec<u8> = vec![0; 256];
+    for index in 0..4 {
+        reader.read_data_at(index, &mut data).unwrap();
+        assert_eq!(data, common::data(index, 256));
+    }
+
+    let mut noise: Vec<u8> = (0..256u32).map(|n| (n * 7919 % 251) as u8).collect();
+    assert!(matches!(
+        Writer::new(&mut file).unwrap().append(&mut noise),
+        Err(Error::CompressedDataTooLarge)
+    ));
+    assert_eq!(Reader::new(&mut file).block_count().unwrap(), 4);
+}
+
+#[cfg(feature = "lz4")]
+#[test]
+fn lz4_round_trip() {
+    round_trip(Compression::Lz4, false);
+}
+
+#[cfg(feature = "zstd")]
+#[test]
+fn zstd_round_trip() {
+    round_trip(Compression::Zstd(3), true);
+}
+
+#[cfg(not(feature = "lz4"))]
+#[test]
+fn lz4_requires_its_feature() {
+    let dir = tempfile::tempdir().unwrap();
+    let options: Options = Options {
+        compression: Compression::Lz4,
+        ..Options::default()
+    };
+    assert!(matches!(
+        common::create_chain(&dir.path().join("chain.blk"), 1, 16, options),
+        Err(Error::FeatureNotEnabled("lz4"))
+    ));
+}
+
+#[test]
+fn uncompressed_chains_are_unchanged() {
+    let dir = tempfile::tempdir().unwrap();
+    let file: File =
+        common::create_chain(&dir.path().join("chain.blk"), 1, 16, Options::default()).unwrap();
+    assert_eq!(file.compression(), Compression::None);
+    assert_eq!(file.block_size(), 32 + 16);
+}
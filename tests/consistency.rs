@@ -0,0 +1,65 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+mod common;
+
+use bc_hash::sha256::Digest;
+use bc_io::io::{File, Options, Reader};
+use bc_io::merkle::{ConsistencyProof, MerkleTree};
+
+#[test]
+fn earlier_trees_are_prefixes_of_later_ones() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut file: File =
+        common::create_chain(&dir.path().join("chain.blk"), 9, 16, Options::default()).unwrap();
+    let tree: MerkleTree = MerkleTree::from_reader(&mut Reader::new(&mut file)).unwrap();
+    for second in 1..=9 {
+        let second_root: Digest = tree.root_at(second).unwrap();
+        for first in 1..=second {
+            let first_root: Digest = tree.root_at(first).unwrap();
+            let proof: ConsistencyProof = tree.prove_consistency(first, second).unwrap();
+            assert!(proof.verify(&first_root, &second_root).unwrap());
+            let proof: ConsistencyProof =
+                ConsistencyProof::from_bytes(&proof.to_bytes().unwrap()).unwrap();
+            assert!(proof.verify(&first_root, &second_root).unwrap());
+        }
+    }
+}
+
+#[test]
+fn rewritten_history_is_detected() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut file: File =
+        common::create_chain(&dir.path().join("a.blk"), 6, 16, Options::default()).unwrap();
+    let tree: MerkleTree = MerkleTree::from_reader(&mut Reader::new(&mut file)).unwrap();
+    let mut file: File =
+        common::create_chain(&dir.path().join("b.blk"), 3, 8, Options::default()).unwrap();
+    let other: MerkleTree = MerkleTree::from_reader(&mut Reader::new(&mut file)).unwrap();
+    let proof: ConsistencyProof = tree.prove_consistency(3, 6).unwrap();
+    assert!(!proof
+        .verify(&other.root().unwrap(), &tree.root().unwrap())
+        .unwrap());
+    assert!(!proof
+        .verify(&tree.root_at(3).unwrap(), &tree.root_at(5).unwrap())
+        .unwrap());
+    assert!(tree.prove_consistency(4, 7).is_err());
+}
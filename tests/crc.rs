@@ -0,0 +1,71 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+mod common;
+
+use bc_io::io::{Error, File, Options, Reader};
+
+#[test]
+fn corrupted_blocks_fail_their_checksum() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("chain.blk");
+    let options: Options = Options {
+        crc: true,
+        ..Options::default()
+    };
+    let block_size: usize = common::create_chain(&path, 3, 16, options)
+        .unwrap()
+        .block_size();
+    assert_eq!(block_size, 32 + 16 + 4);
+    let mut file: File = File::open_existing(&path).unwrap();
+    Reader::new(&mut file).validate_all_blocks().unwrap();
+
+    common::corrupt(&path, block_size + 40);
+    let mut file: File = File::open_existing(&path).unwrap();
+    let mut reader: Reader = Reader::new(&mut file);
+    let mut block: Vec<u8> = vec![0; block_size];
+    reader.read_block_at(0, &mut block).unwrap();
+    assert!(matches!(
+        reader.read_block_at(1, &mut block),
+        Err(Error::InvalidChecksum(1))
+    ));
+    assert!(matches!(
+        reader.validate_all_blocks(),
+        Err(Error::InvalidChecksum(1))
+    ));
+}
+
+#[test]
+fn without_a_checksum_corruption_breaks_the_next_link() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("chain.blk");
+    let block_size: usize = common::create_chain(&path, 3, 16, Options::default())
+        .unwrap()
+        .block_size();
+    assert_eq!(block_size, 32 + 16);
+    common::corrupt(&path, block_size + 40);
+    let mut file: File = File::open_existing(&path).unwrap();
+    assert!(matches!(
+        Reader::new(&mut file).validate_all_blocks(),
+        Err(Error::InvalidBlockHash(2))
+    ));
+}
@@ -0,0 +1,73 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+use bc_io::io::{Deserialize, Digest, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Record {
+    kind: u8,
+    flags: u16,
+    timestamp: i64,
+    tag: [u8; 4],
+    root: Digest,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Pair(u32, i32);
+
+#[test]
+fn round_trip() {
+    let record: Record = Record {
+        kind: 7,
+        flags: 0x0102,
+        timestamp: -5,
+        tag: *b"abcd",
+        root: Digest::from(&b"hello"[..]),
+    };
+    assert_eq!(Record::SIZE, 1 + 2 + 8 + 4 + 32);
+    let mut buf: Vec<u8> = vec![0; Record::SIZE];
+    record.serialize(&mut buf).unwrap();
+    assert_eq!(&buf[0..3], &[7, 0x02, 0x01]);
+    assert_eq!(&buf[3..11], &(-5i64).to_le_bytes());
+    assert_eq!(&buf[11..15], b"abcd");
+    assert_eq!(Record::deserialize(&buf).unwrap(), record);
+}
+
+#[test]
+fn tuple_struct_round_trip() {
+    let mut buf: Vec<u8> = vec![0; Pair::SIZE];
+    Pair(1, -1).serialize(&mut buf).unwrap();
+    assert_eq!(Pair::deserialize(&buf).unwrap(), Pair(1, -1));
+}
+
+#[test]
+fn wrong_length_is_rejected() {
+    let mut buf: Vec<u8> = vec![0; Pair::SIZE + 1];
+    assert!(Pair(1, 2).serialize(&mut buf).is_err());
+    assert!(Pair::deserialize(&buf[1..Pair::SIZE]).is_err());
+}
+
+#[test]
+fn unsupported_fields_do_not_compile() {
+    let t: trybuild::TestCases = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
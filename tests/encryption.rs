@@ -0,0 +1,105 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+mod common;
+
+use bc_io::encryption::EncryptionKey;
+use bc_io::io::{Error, File, Options, Reader};
+
+/// Returns an encryption key whose bytes are all ```byte```.
+fn key(byte: u8) -> EncryptionKey {
+    *EncryptionKey::from_slice(&[byte; 32])
+}
+
+#[test]
+fn data_sections_are_encrypted_at_rest() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("chain.blk");
+    let options: Options = Options {
+        encryption_key: Some(key(0x42)),
+        ..Options::default()
+    };
+    drop(common::create_chain(&path, 3, 64, options).unwrap());
+    let bytes: Vec<u8> = std::fs::read(&path).unwrap();
+    let plaintext: Vec<u8> = common::data(2, 64);
+    assert!(!bytes
+        .windows(64)
+        .any(|window| window == plaintext.as_slice()));
+
+    let mut file: File = File::open_existing(&path).unwrap();
+    assert!(file.is_encrypted());
+    assert_eq!(file.data_size(), 64);
+    let mut reader: Reader = Reader::new(&mut file);
+    reader.validate_all_blocks().unwrap();
+    let mut data: Vec<u8> = vec![0; 64];
+    assert!(matches!(
+        reader.read_data_at(2, &mut data),
+        Err(Error::MissingEncryptionKey)
+    ));
+
+    file.set_encryption_key(key(0x24));
+    assert!(matches!(
+        Reader::new(&mut file).read_data_at(2, &mut data),
+        Err(Error::InvalidCiphertext)
+    ));
+
+    file.set_encryption_key(key(0x42));
+    Reader::new(&mut file).read_data_at(2, &mut data).unwrap();
+    assert_eq!(data, plaintext);
+}
+
+#[test]
+fn tampered_ciphertext_is_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("chain.blk");
+    let options: Options = Options {
+        encryption_key: Some(key(0x42)),
+        ..Options::default()
+    };
+    let block_size: usize = common::create_chain(&path, 2, 64, options)
+        .unwrap()
+        .block_size();
+    common::corrupt(&path, block_size + 60);
+    let mut file: File = File::open_existing(&path).unwrap();
+    file.set_encryption_key(key(0x42));
+    let mut data: Vec<u8> = vec![0; 64];
+    assert!(matches!(
+        Reader::new(&mut file).read_data_at(1, &mut data),
+        Err(Error::InvalidCiphertext)
+    ));
+}
+
+#[test]
+fn keys_are_not_debug_printed() {
+    let dir = tempfile::tempdir().unwrap();
+    let options: Options = Options {
+        encryption_key: Some(key(0x42)),
+        ..Options::default()
+    };
+    let printed: String = format!("{:?}", options);
+    assert!(printed.contains("encryption_key: Some(<redacted>)"));
+    assert!(!printed.contains("66, 66"));
+    let file: File = common::create_chain(&dir.path().join("chain.blk"), 1, 16, options).unwrap();
+    let printed: String = format!("{:?}", file);
+    assert!(printed.contains("encryption_key: Some(<redacted>)"));
+    assert!(!printed.contains("66, 66"));
+}
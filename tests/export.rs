@@ -0,0 +1,114 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+mod common;
+
+use bc_hash::sha256::{Digest, DIGEST_SIZE};
+use bc_io::export::{self, Encoding};
+use bc_io::io::{Options, Reader};
+use bc_io::merkle::MerkleTree;
+use serde_json::Value;
+
+/// Parses each line of ```out``` as a JSON object.
+fn parse(out: Vec<u8>) -> Vec<Value> {
+    String::from_utf8(out)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+#[test]
+fn jsonl_has_one_object_per_block() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut file =
+        common::create_chain(&dir.path().join("chain.blk"), 5, 16, Options::default()).unwrap();
+    let mut out: Vec<u8> = Vec::new();
+    assert_eq!(
+        export::to_jsonl(&mut Reader::new(&mut file), &mut out, Encoding::Hex).unwrap(),
+        5
+    );
+    let lines: Vec<Value> = parse(out);
+    assert_eq!(lines.len(), 5);
+    for (index, record) in lines.iter().enumerate() {
+        assert_eq!(record["index"], index as u64);
+        let data: String = Encoding::Hex.encode(&common::data(index as u64, 16));
+        assert_eq!(record["data"], data.as_str());
+    }
+    assert_eq!(lines[1]["prev_hash"], lines[0]["hash"]);
+}
+
+#[test]
+fn jsonl_with_trailer_ends_with_the_merkle_root() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut file =
+        common::create_chain(&dir.path().join("chain.blk"), 5, 16, Options::default()).unwrap();
+    let mut reader: Reader = Reader::new(&mut file);
+    let mut out: Vec<u8> = Vec::new();
+    assert_eq!(
+        export::to_jsonl_with_trailer(&mut reader, &mut out, Encoding::Hex).unwrap(),
+        5
+    );
+    let lines: Vec<Value> = parse(out);
+    assert_eq!(lines.len(), 6);
+    assert_eq!(lines[4]["index"], 4);
+
+    let root: Digest = MerkleTree::from_reader(&mut reader)
+        .unwrap()
+        .root()
+        .unwrap();
+    let mut bytes: [u8; DIGEST_SIZE] = [0; DIGEST_SIZE];
+    root.serialize(&mut bytes).unwrap();
+    assert_eq!(lines[5]["block_count"], 5);
+    assert_eq!(
+        lines[5]["merkle_root"],
+        Encoding::Hex.encode(&bytes).as_str()
+    );
+}
+
+#[test]
+fn csv_has_a_header_and_a_row_per_block() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut file =
+        common::create_chain(&dir.path().join("chain.blk"), 3, 8, Options::default()).unwrap();
+    let mut out: Vec<u8> = Vec::new();
+    export::to_csv(&mut Reader::new(&mut file), &mut out, Encoding::Base64).unwrap();
+    let out: String = String::from_utf8(out).unwrap();
+    let lines: Vec<&str> = out.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert_eq!(lines[0], "index,prev_hash,hash,data");
+    assert!(lines[3].starts_with("2,"));
+    assert!(lines[3].ends_with(&Encoding::Base64.encode(&common::data(2, 8))));
+}
+
+#[test]
+fn encodings_round_trip() {
+    for size in 0..8 {
+        let bytes: Vec<u8> = (0..size).map(|b| b * 37).collect();
+        for encoding in [Encoding::Hex, Encoding::Base64] {
+            assert_eq!(encoding.decode(&encoding.encode(&bytes)).unwrap(), bytes);
+        }
+    }
+    assert_eq!(Encoding::Hex.encode(&[0x0f, 0xa0]), "0fa0");
+    assert_eq!(Encoding::Base64.encode(b"ab"), "YWI=");
+    assert!(Encoding::Hex.decode("0g").is_none());
+}
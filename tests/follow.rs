@@ -0,0 +1,98 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+mod common;
+
+use bc_io::io::{File, Options, Reader, Writer};
+use std::io::Write;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn follow_waits_for_new_blocks() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("chain.blk");
+    let mut file: File = common::create_chain(&path, 2, 16, Options::default()).unwrap();
+    let appender = {
+        let path = path.clone();
+        thread::spawn(move || {
+            let mut file: File = File::open_existing(&path).unwrap();
+            let mut writer: Writer = Writer::new(&mut file).unwrap();
+            for index in 2..4 {
+                thread::sleep(Duration::from_millis(50));
+                writer.append(&mut common::data(index, 16)).unwrap();
+            }
+        })
+    };
+    let mut reader: Reader = Reader::new(&mut file);
+    let mut data: Vec<u8> = vec![0; 16];
+    let blocks: Vec<(u64, Vec<u8>)> = reader
+        .follow_from(1)
+        .interval(Duration::from_millis(10))
+        .take(3)
+        .map(|block| block.unwrap())
+        .collect();
+    appender.join().unwrap();
+    for (n, (index, block)) in blocks.iter().enumerate() {
+        assert_eq!(*index, n as u64 + 1);
+        reader.decode_data(block, &mut data).unwrap();
+        assert_eq!(data, common::data(*index, 16));
+    }
+}
+
+#[test]
+fn partially_written_blocks_are_not_returned_until_complete() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("chain.blk");
+    // the same chain with one more block, whose last block is copied into the followed file
+    let complete = dir.path().join("complete.blk");
+    drop(common::create_chain(&path, 2, 16, Options::default()).unwrap());
+    let block_size: usize = common::create_chain(&complete, 3, 16, Options::default())
+        .unwrap()
+        .block_size();
+    let block: Vec<u8> = std::fs::read(&complete).unwrap()[2 * block_size..].to_vec();
+    let mut tail: std::fs::File = std::fs::File::options().append(true).open(&path).unwrap();
+    tail.write_all(&block[0..block_size / 2]).unwrap();
+    tail.flush().unwrap();
+
+    let (sender, receiver) = mpsc::channel();
+    let follower = {
+        let path = path.clone();
+        thread::spawn(move || {
+            let mut file: File = File::open_growing(&path).unwrap();
+            let mut reader: Reader = Reader::new(&mut file);
+            let follow = reader.follow().unwrap().interval(Duration::from_millis(10));
+            for block in follow.take(1) {
+                sender.send(block.unwrap()).unwrap();
+            }
+        })
+    };
+    assert!(receiver.recv_timeout(Duration::from_millis(200)).is_err());
+    tail.write_all(&block[block_size / 2..]).unwrap();
+    tail.flush().unwrap();
+    let (index, received) = receiver.recv_timeout(Duration::from_secs(10)).unwrap();
+    follower.join().unwrap();
+    assert_eq!(index, 2);
+    assert_eq!(received, block);
+    File::open_existing(&path).unwrap();
+}
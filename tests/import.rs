@@ -0,0 +1,128 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+mod common;
+
+use bc_io::export::{self, Encoding};
+use bc_io::import;
+use bc_io::io::{Error, File, Options, Reader};
+
+/// Exports a chain of ```count``` blocks as JSON lines followed by the Merkle root trailer.
+fn exported(dir: &std::path::Path, count: u64) -> String {
+    let mut file: File =
+        common::create_chain(&dir.join("chain.blk"), count, 16, Options::default()).unwrap();
+    let mut out: Vec<u8> = Vec::new();
+    export::to_jsonl_with_trailer(&mut Reader::new(&mut file), &mut out, Encoding::Base64).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let jsonl: String = exported(dir.path(), 4);
+    let path = dir.path().join("imported.blk");
+    let mut file: File =
+        import::from_jsonl(jsonl.as_bytes(), &path, Encoding::Base64, true).unwrap();
+    let mut reader: Reader = Reader::new(&mut file);
+    assert_eq!(reader.block_count().unwrap(), 4);
+    reader.validate_all_blocks().unwrap();
+    let mut data: Vec<u8> = vec![0; 16];
+    reader.read_data_at(3, &mut data).unwrap();
+    assert_eq!(data, common::data(3, 16));
+    assert_eq!(
+        std::fs::read(&path).unwrap(),
+        std::fs::read(dir.path().join("chain.blk")).unwrap()
+    );
+
+    let mut out: Vec<u8> = Vec::new();
+    export::to_jsonl(&mut reader, &mut out, Encoding::Base64).unwrap();
+    let again = dir.path().join("again.blk");
+    drop(import::from_jsonl(&out[..], &again, Encoding::Base64, true).unwrap());
+    assert_eq!(
+        std::fs::read(&again).unwrap(),
+        std::fs::read(&path).unwrap()
+    );
+}
+
+#[test]
+fn broken_link_is_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let jsonl: String = exported(dir.path(), 3);
+    let mut lines: Vec<serde_json::Value> = jsonl
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    lines[2]["prev_hash"] = lines[0]["hash"].clone();
+    let broken: String = lines.iter().map(|line| format!("{}\n", line)).collect();
+    let path = dir.path().join("imported.blk");
+    assert!(matches!(
+        import::from_jsonl(broken.as_bytes(), &path, Encoding::Base64, true),
+        Err(Error::InvalidBlockHash(2))
+    ));
+    assert!(!path.exists());
+    import::from_jsonl(jsonl.as_bytes(), &path, Encoding::Base64, true).unwrap();
+}
+
+#[test]
+fn trailer_is_verified() {
+    let dir = tempfile::tempdir().unwrap();
+    let jsonl: String = exported(dir.path(), 3);
+    let mut lines: Vec<serde_json::Value> = jsonl
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    lines[3]["merkle_root"] = lines[2]["hash"].clone();
+    let jsonl: String = lines.iter().map(|line| format!("{}\n", line)).collect();
+    assert!(matches!(
+        import::from_jsonl(
+            jsonl.as_bytes(),
+            &dir.path().join("a.blk"),
+            Encoding::Base64,
+            true
+        ),
+        Err(Error::InvalidMerkleRoot)
+    ));
+    assert!(import::from_jsonl(
+        jsonl.as_bytes(),
+        &dir.path().join("b.blk"),
+        Encoding::Base64,
+        false
+    )
+    .is_ok());
+}
+
+#[test]
+fn records_after_the_trailer_are_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let jsonl: String = exported(dir.path(), 2);
+    let last: &str = jsonl.lines().nth(1).unwrap();
+    let jsonl: String = format!("{}{}\n", jsonl, last);
+    assert!(matches!(
+        import::from_jsonl(
+            jsonl.as_bytes(),
+            &dir.path().join("a.blk"),
+            Encoding::Base64,
+            false
+        ),
+        Err(Error::InvalidRecord(4))
+    ));
+}
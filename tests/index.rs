@@ -0,0 +1,162 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+mod common;
+
+use bc_io::index::KeyIndex;
+use bc_io::io::{Error, File, Options, Reader, Result, Writer};
+use std::path::Path;
+
+/// Keys each block written by ```common::data()``` by its index modulo 3.
+fn key(data: &[u8]) -> Result<Vec<u8>> {
+    Ok(vec![data[0] % 3])
+}
+
+/// Opens the index called "mod3" of ```file```.
+fn open(file: &mut File) -> KeyIndex {
+    KeyIndex::open(&mut Reader::new(file), "mod3", key).unwrap()
+}
+
+#[test]
+fn blocks_are_found_by_key() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("chain.blk");
+    let mut file: File = common::create_chain(&path, 5, 16, Options::default()).unwrap();
+    let mut index: KeyIndex = open(&mut file);
+    assert!(KeyIndex::path(&path, "mod3").exists());
+    assert_eq!(index.len(), 5);
+    assert_eq!(index.get(&[0]), &[0, 3]);
+    assert_eq!(index.get(&[1]), &[1, 4]);
+    assert!(index.get(&[7]).is_empty());
+
+    index
+        .append(
+            &mut Writer::new(&mut file).unwrap(),
+            &mut common::data(5, 16),
+        )
+        .unwrap();
+    assert_eq!(index.get(&[2]), &[2, 5]);
+    drop(index);
+
+    Writer::new(&mut file)
+        .unwrap()
+        .append(&mut common::data(6, 16))
+        .unwrap();
+    let mut index: KeyIndex = open(&mut file);
+    assert_eq!(index.len(), 7);
+    assert_eq!(index.get(&[0]), &[0, 3, 6]);
+    Writer::new(&mut file)
+        .unwrap()
+        .append(&mut common::data(9, 16))
+        .unwrap();
+    assert_eq!(
+        Reader::new(&mut file)
+            .find_by_key(&mut index, &[0])
+            .unwrap(),
+        vec![0, 3, 6, 7]
+    );
+}
+
+#[test]
+fn appending_to_a_stale_index_fails() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut file: File =
+        common::create_chain(&dir.path().join("chain.blk"), 2, 16, Options::default()).unwrap();
+    let mut index: KeyIndex = open(&mut file);
+    Writer::new(&mut file)
+        .unwrap()
+        .append(&mut common::data(2, 16))
+        .unwrap();
+    assert!(matches!(
+        index.append(
+            &mut Writer::new(&mut file).unwrap(),
+            &mut common::data(3, 16)
+        ),
+        Err(Error::IndexStale)
+    ));
+    assert_eq!(index.update(&mut Reader::new(&mut file)).unwrap(), 1);
+    index
+        .append(
+            &mut Writer::new(&mut file).unwrap(),
+            &mut common::data(3, 16),
+        )
+        .unwrap();
+    assert_eq!(index.get(&[0]), &[0, 3]);
+    assert_eq!(index.get(&[2]), &[2]);
+}
+
+#[test]
+fn blocks_are_not_written_when_their_key_can_not_be_extracted() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut file: File =
+        common::create_chain(&dir.path().join("chain.blk"), 2, 16, Options::default()).unwrap();
+    let mut index: KeyIndex = KeyIndex::open(&mut Reader::new(&mut file), "small", |data| {
+        if data[0] < 2 {
+            Ok(vec![data[0]])
+        } else {
+            Err(Error::InvalidRecord(data[0] as u64))
+        }
+    })
+    .unwrap();
+    assert!(matches!(
+        index.append(
+            &mut Writer::new(&mut file).unwrap(),
+            &mut common::data(2, 16)
+        ),
+        Err(Error::InvalidRecord(2))
+    ));
+    assert_eq!(Reader::new(&mut file).block_count().unwrap(), 2);
+    assert_eq!(index.len(), 2);
+}
+
+/// Creates a chain at ```path``` whose blocks are ```data(first)```, ```data(first + 1)```, ...
+fn create_from(path: &Path, first: u64, count: u64) -> File {
+    let mut file: File =
+        File::create_new(path, &mut common::Raw(&common::data(first, 16)), 16).unwrap();
+    let mut writer: Writer = Writer::new(&mut file).unwrap();
+    for index in first + 1..first + count {
+        writer.append(&mut common::data(index, 16)).unwrap();
+    }
+    drop(writer);
+    file
+}
+
+#[test]
+fn indexes_of_another_chain_are_rebuilt() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("chain.blk");
+    let mut file: File = create_from(&path, 0, 4);
+    drop(open(&mut file));
+    drop(file);
+
+    std::fs::remove_file(&path).unwrap();
+    let mut file: File = create_from(&path, 1, 4);
+    let index: KeyIndex = open(&mut file);
+    assert_eq!(index.len(), 4);
+    assert_eq!(index.get(&[1]), &[0, 3]);
+
+    std::fs::remove_file(&path).unwrap();
+    let mut file: File = create_from(&path, 0, 2);
+    let index: KeyIndex = open(&mut file);
+    assert_eq!(index.len(), 2);
+    assert_eq!(index.get(&[0]), &[0]);
+}
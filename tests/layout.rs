@@ -0,0 +1,77 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+use bc_hash::sha256::Digest;
+use bc_io::io::Error;
+use bc_io::layout::{FieldCursor, Layout};
+
+const RECORD: Layout = Layout::new().u8().i16().u32().i64().digest().bytes(3);
+
+#[test]
+fn layouts_add_up_their_fields() {
+    assert_eq!(RECORD.size(), 1 + 2 + 4 + 8 + 32 + 3);
+    assert_eq!(Layout::new().i64().u64().digest().size(), 48);
+    assert_eq!(
+        Layout::new().u8().u16().u32().u64().size(),
+        Layout::new().i8().i16().i32().i64().size()
+    );
+    assert_eq!(Layout::new().offset(), 0);
+    assert_eq!(Layout::new().u8().i16().offset(), 3);
+    assert_eq!(RECORD.offset(), RECORD.size());
+}
+
+#[test]
+fn cursors_write_and_read_every_field() {
+    let digest: Digest = Digest::from(&b"layout"[..]);
+    let mut buf: Vec<u8> = vec![0; RECORD.size()];
+    let mut cursor: FieldCursor<&mut [u8]> =
+        FieldCursor::with_layout(&mut buf[..], &RECORD).unwrap();
+    cursor.put_u8(7).unwrap();
+    cursor.put_i16_le(-2).unwrap();
+    cursor.put_u32_le(0x01020304).unwrap();
+    cursor.put_i64_le(i64::MIN).unwrap();
+    cursor.put_digest(&digest).unwrap();
+    cursor.put_bytes(b"abc").unwrap();
+    cursor.finish().unwrap();
+    assert!(matches!(cursor.put_u8(0), Err(Error::InvalidSliceLength)));
+    assert_eq!(&buf[3..7], &[4, 3, 2, 1]);
+
+    let mut cursor: FieldCursor<&[u8]> = FieldCursor::with_layout(&buf[..], &RECORD).unwrap();
+    assert_eq!(cursor.get_u8().unwrap(), 7);
+    assert_eq!(cursor.get_i16_le().unwrap(), -2);
+    assert_eq!(cursor.get_u32_le().unwrap(), 0x01020304);
+    assert_eq!(cursor.get_i64_le().unwrap(), i64::MIN);
+    assert_eq!(cursor.get_digest().unwrap(), digest);
+    assert_eq!(cursor.remaining(), 3);
+    assert!(cursor.finish().is_err());
+    assert_eq!(cursor.get_bytes::<3>().unwrap(), *b"abc");
+    assert!(matches!(cursor.get_u8(), Err(Error::InvalidSliceLength)));
+}
+
+#[test]
+fn buffers_must_match_the_layout() {
+    let buf: Vec<u8> = vec![0; RECORD.size() - 1];
+    assert!(matches!(
+        FieldCursor::with_layout(&buf[..], &RECORD),
+        Err(Error::InvalidSliceLength)
+    ));
+}
@@ -0,0 +1,97 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+mod common;
+
+use bc_hash::sha256::Digest;
+use bc_io::io::{File, Options, Reader, Writer};
+use bc_io::merkle::{InclusionProof, MerkleTree};
+
+/// Returns the hash of every block in the stream.
+fn block_hashes(reader: &mut Reader) -> Vec<Digest> {
+    let mut block: Vec<u8> = vec![0; reader.block_size()];
+    (0..reader.block_count().unwrap())
+        .map(|index| {
+            reader.read_block_at(index, &mut block).unwrap();
+            reader.block_hash(&block).unwrap()
+        })
+        .collect()
+}
+
+#[test]
+fn every_block_has_an_inclusion_proof() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut file: File =
+        common::create_chain(&dir.path().join("chain.blk"), 7, 16, Options::default()).unwrap();
+    let mut reader: Reader = Reader::new(&mut file);
+    let hashes: Vec<Digest> = block_hashes(&mut reader);
+    let tree: MerkleTree = MerkleTree::from_reader(&mut reader).unwrap();
+    let root: Digest = tree.root().unwrap();
+    assert_eq!(tree.len(), 7);
+    for (index, hash) in hashes.iter().enumerate() {
+        let proof: InclusionProof = tree.prove(index as u64).unwrap();
+        assert!(proof.verify(hash, &root).unwrap());
+        assert!(!proof.verify(&hashes[(index + 1) % 7], &root).unwrap());
+        let proof: InclusionProof = InclusionProof::from_bytes(&proof.to_bytes().unwrap()).unwrap();
+        assert!(proof.verify(hash, &root).unwrap());
+    }
+    assert!(tree.prove(7).is_err());
+}
+
+#[test]
+fn roots_of_earlier_sizes_match_smaller_trees() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut file: File =
+        common::create_chain(&dir.path().join("chain.blk"), 6, 16, Options::default()).unwrap();
+    let mut reader: Reader = Reader::new(&mut file);
+    let hashes: Vec<Digest> = block_hashes(&mut reader);
+    let tree: MerkleTree = MerkleTree::from_reader(&mut reader).unwrap();
+    let mut smaller: MerkleTree = MerkleTree::new();
+    for (index, hash) in hashes.iter().enumerate() {
+        smaller.push(hash).unwrap();
+        let size: u64 = index as u64 + 1;
+        assert_eq!(tree.root_at(size).unwrap(), smaller.root().unwrap());
+        let proof: InclusionProof = tree.prove_at(0, size).unwrap();
+        assert!(proof.verify(&hashes[0], &smaller.root().unwrap()).unwrap());
+    }
+}
+
+#[test]
+fn update_catches_up_with_the_stream() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("chain.blk");
+    let mut file: File = common::create_chain(&path, 3, 16, Options::default()).unwrap();
+    let mut tree: MerkleTree = MerkleTree::from_reader(&mut Reader::new(&mut file)).unwrap();
+    {
+        let mut writer: Writer = Writer::new(&mut file).unwrap();
+        writer.append(&mut common::data(3, 16)).unwrap();
+    }
+    let mut reader: Reader = Reader::new(&mut file);
+    assert_eq!(tree.update(&mut reader).unwrap(), 1);
+    assert_eq!(
+        tree.root().unwrap(),
+        MerkleTree::from_reader(&mut reader)
+            .unwrap()
+            .root()
+            .unwrap()
+    );
+}
@@ -0,0 +1,122 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+mod common;
+
+use bc_io::io::{Error, File, Options, Reader, Writer};
+use bc_io::migrate::{self, Migration};
+
+#[test]
+fn rewrite_changes_the_block_size_and_records_the_tips() {
+    let dir = tempfile::tempdir().unwrap();
+    let old_path = dir.path().join("old.blk");
+    let new_path = dir.path().join("new.blk");
+    let mut old: File = common::create_chain(&old_path, 4, 16, Options::default()).unwrap();
+    let old_tip = Writer::new(&mut old).unwrap().last_hash().clone();
+    let migration: Migration = migrate::rewrite(
+        &mut Reader::new(&mut old),
+        &new_path,
+        24,
+        Options::default(),
+        |index, old, new| {
+            new[0..16].copy_from_slice(old);
+            new[16..24].copy_from_slice(&(index * 10).to_le_bytes());
+            Ok(())
+        },
+    )
+    .unwrap();
+    assert_eq!(migration.block_count, 4);
+    assert_eq!(migration.old_tip, old_tip);
+    assert_eq!(Migration::read(&new_path).unwrap(), migration);
+
+    let mut new: File = File::open_existing(&new_path).unwrap();
+    assert_eq!(new.data_size(), 24);
+    assert_eq!(
+        *Writer::new(&mut new).unwrap().last_hash(),
+        migration.new_tip
+    );
+    let mut reader: Reader = Reader::new(&mut new);
+    assert_eq!(reader.block_count().unwrap(), 4);
+    reader.validate_all_blocks().unwrap();
+    let mut data: Vec<u8> = vec![0; 24];
+    reader.read_data_at(3, &mut data).unwrap();
+    assert_eq!(&data[0..16], common::data(3, 16).as_slice());
+    assert_eq!(&data[16..24], &30u64.to_le_bytes());
+}
+
+#[test]
+fn broken_links_stop_the_rewrite() {
+    let dir = tempfile::tempdir().unwrap();
+    let old_path = dir.path().join("old.blk");
+    let block_size: usize = common::create_chain(&old_path, 4, 16, Options::default())
+        .unwrap()
+        .block_size();
+    common::corrupt(&old_path, block_size + 40);
+    let mut old: File = File::open_existing(&old_path).unwrap();
+    let result = migrate::rewrite(
+        &mut Reader::new(&mut old),
+        &dir.path().join("new.blk"),
+        16,
+        Options::default(),
+        |_, old, new| {
+            new.copy_from_slice(old);
+            Ok(())
+        },
+    );
+    assert!(matches!(result, Err(Error::InvalidBlockHash(2))));
+    assert!(!dir.path().join("new.blk").exists());
+    assert!(!Migration::path(&dir.path().join("new.blk")).exists());
+}
+
+#[test]
+fn a_failed_rewrite_can_be_retried() {
+    let dir = tempfile::tempdir().unwrap();
+    let old_path = dir.path().join("old.blk");
+    let new_path = dir.path().join("new.blk");
+    let mut old: File = common::create_chain(&old_path, 4, 16, Options::default()).unwrap();
+    let result = migrate::rewrite(
+        &mut Reader::new(&mut old),
+        &new_path,
+        16,
+        Options::default(),
+        |index, _, _| match index {
+            3 => Err(Error::InvalidSliceLength),
+            _ => Ok(()),
+        },
+    );
+    assert!(matches!(result, Err(Error::InvalidSliceLength)));
+    assert!(!new_path.exists());
+
+    let migration: Migration = migrate::rewrite(
+        &mut Reader::new(&mut old),
+        &new_path,
+        16,
+        Options::default(),
+        |_, old, new| {
+            new.copy_from_slice(old);
+            Ok(())
+        },
+    )
+    .unwrap();
+    assert_eq!(migration.block_count, 4);
+    assert_eq!(Migration::read(&new_path).unwrap(), migration);
+}
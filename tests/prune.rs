@@ -0,0 +1,91 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+mod common;
+
+use bc_io::io::{Error, Options};
+use bc_io::segment::{Checkpoint, Rollover, SegmentedChain};
+use std::path::Path;
+
+/// Creates a segmented chain at ```base``` with ```count``` blocks of 16 bytes and three
+/// blocks per segment.
+fn create(base: &Path, count: u64) -> SegmentedChain {
+    let mut chain: SegmentedChain = SegmentedChain::create_new(
+        base,
+        &mut common::Raw(&common::data(0, 16)),
+        16,
+        Options::default(),
+        Rollover::Blocks(3),
+    )
+    .unwrap();
+    for index in 1..count {
+        chain.append(&mut common::data(index, 16)).unwrap();
+    }
+    chain
+}
+
+#[test]
+fn pruned_chains_remain_verifiable() {
+    let dir = tempfile::tempdir().unwrap();
+    let base = dir.path().join("chain");
+    let mut chain: SegmentedChain = create(&base, 8);
+    let checkpoint: Checkpoint = chain.prune(2).unwrap();
+    assert_eq!(checkpoint.segment, 3);
+    assert_eq!(checkpoint.index, 6);
+    assert_eq!(chain.first_index(), 6);
+    assert!(!SegmentedChain::segment_path(&base, 1).exists());
+    assert!(!SegmentedChain::segment_path(&base, 2).exists());
+    assert!(SegmentedChain::checkpoint_path(&base).exists());
+    chain.validate_all_blocks().unwrap();
+    let mut data: Vec<u8> = vec![0; 16];
+    assert!(matches!(
+        chain.read_data_at(5, &mut data),
+        Err(Error::BlockPruned(5))
+    ));
+    drop(chain);
+
+    let mut chain: SegmentedChain =
+        SegmentedChain::open_existing(&base, Rollover::Blocks(3)).unwrap();
+    assert_eq!(chain.checkpoint(), Some(&checkpoint));
+    assert_eq!(chain.block_count().unwrap(), 8);
+    chain.validate_all_blocks().unwrap();
+    chain.read_data_at(7, &mut data).unwrap();
+    assert_eq!(data, common::data(7, 16));
+    chain.append(&mut common::data(8, 16)).unwrap();
+    chain.append(&mut common::data(9, 16)).unwrap();
+    assert_eq!(chain.segment_count(), 2);
+    chain.validate_all_blocks().unwrap();
+    assert!(matches!(chain.prune(2), Err(Error::BlockNumDoesNotExist)));
+}
+
+#[test]
+fn archived_segments_are_moved() {
+    let dir = tempfile::tempdir().unwrap();
+    let archive = tempfile::tempdir().unwrap();
+    let base = dir.path().join("chain");
+    let mut chain: SegmentedChain = create(&base, 7);
+    chain.archive(1, archive.path()).unwrap();
+    assert!(!SegmentedChain::segment_path(&base, 1).exists());
+    assert!(archive.path().join("chain.000001.blk").exists());
+    assert_eq!(chain.first_index(), 3);
+    chain.validate_all_blocks().unwrap();
+}
@@ -0,0 +1,75 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+mod common;
+
+use bc_io::io::{File, KeyRange, Reader, Result, Writer};
+
+/// Returns the timestamp at the start of a data section.
+fn timestamp(data: &[u8]) -> Result<u64> {
+    Ok(u64::from_le_bytes(data[0..8].try_into().unwrap()))
+}
+
+/// Creates a chain whose blocks have the given timestamps.
+fn create(path: &std::path::Path, timestamps: &[u64]) -> File {
+    let mut file: File =
+        File::create_new(path, &mut common::Raw(&common::data(timestamps[0], 16)), 16).unwrap();
+    let mut writer: Writer = Writer::new(&mut file).unwrap();
+    for t in &timestamps[1..] {
+        writer.append(&mut common::data(*t, 16)).unwrap();
+    }
+    drop(writer);
+    file
+}
+
+#[test]
+fn ranges_are_found_by_binary_search() {
+    let dir = tempfile::tempdir().unwrap();
+    let timestamps: Vec<u64> = vec![0, 10, 20, 20, 30, 40, 50, 60];
+    let mut file: File = create(&dir.path().join("chain.blk"), &timestamps);
+    let mut reader: Reader = Reader::new(&mut file);
+    let mut range = |lo: u64, hi: u64| {
+        let range: KeyRange = reader.range_by(timestamp, &lo, &hi).unwrap();
+        assert!(!range.scanned);
+        range.blocks
+    };
+    assert_eq!(range(15, 45), vec![2, 3, 4, 5]);
+    assert_eq!(range(20, 21), vec![2, 3]);
+    assert_eq!(range(0, 1000), (0..8).collect::<Vec<u64>>());
+    assert!(range(61, 100).is_empty());
+    assert!(range(30, 30).is_empty());
+    assert!(range(45, 15).is_empty());
+}
+
+#[test]
+fn keys_out_of_order_fall_back_to_a_scan() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut file: File = create(&dir.path().join("chain.blk"), &[0, 10, 20, 5, 40, 50]);
+    let mut reader: Reader = Reader::new(&mut file);
+    assert_eq!(
+        reader.range_by(timestamp, &5, &11).unwrap(),
+        KeyRange {
+            blocks: vec![1, 3],
+            scanned: true,
+        }
+    );
+}
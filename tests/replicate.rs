@@ -0,0 +1,178 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+mod common;
+
+use bc_io::io::{Error, File, Options, Writer};
+use bc_io::replicate::{Replica, Server};
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Mutex;
+use std::thread;
+
+#[test]
+fn replicas_catch_up_and_follow_the_primary() {
+    let dir = tempfile::tempdir().unwrap();
+    let primary_path = dir.path().join("primary.blk");
+    let replica_path = dir.path().join("replica.blk");
+    let mut primary: File = common::create_chain(&primary_path, 5, 16, Options::default()).unwrap();
+    let mut replica: File = common::create_chain(&replica_path, 2, 16, Options::default()).unwrap();
+
+    let server: Server = Server::bind(&primary_path, "127.0.0.1:0").unwrap();
+    let addr: SocketAddr = server.local_addr().unwrap();
+    thread::spawn(move || server.serve_one());
+
+    let mut replica: Replica = Replica::connect(addr, &mut replica).unwrap();
+    assert_eq!(replica.primary_count(), 5);
+    assert_eq!(replica.catch_up().unwrap(), 3);
+    assert_eq!(replica.block_count().unwrap(), 5);
+
+    let mut writer: Writer = Writer::new(&mut primary).unwrap();
+    writer.append(&mut common::data(5, 16)).unwrap();
+    writer.append(&mut common::data(6, 16)).unwrap();
+    let indexes: Vec<u64> = replica
+        .by_ref()
+        .take(2)
+        .map(|index| index.unwrap())
+        .collect();
+    assert_eq!(indexes, vec![5, 6]);
+    assert_eq!(replica.last_hash(), writer.last_hash());
+    drop(replica);
+    assert_eq!(
+        std::fs::read(&replica_path).unwrap(),
+        std::fs::read(&primary_path).unwrap()
+    );
+}
+
+#[test]
+fn diverged_replicas_are_turned_away() {
+    let dir = tempfile::tempdir().unwrap();
+    let primary_path = dir.path().join("primary.blk");
+    drop(common::create_chain(&primary_path, 3, 16, Options::default()).unwrap());
+    let mut replica: File = File::create_new(
+        &dir.path().join("replica.blk"),
+        &mut common::Raw(&common::data(7, 16)),
+        16,
+    )
+    .unwrap();
+    Writer::new(&mut replica)
+        .unwrap()
+        .append(&mut common::data(1, 16))
+        .unwrap();
+
+    let server: Server = Server::bind(&primary_path, "127.0.0.1:0").unwrap();
+    let addr: SocketAddr = server.local_addr().unwrap();
+    let (sender, errors) = mpsc::channel();
+    let sender: Mutex<mpsc::Sender<Error>> = Mutex::new(sender);
+    thread::spawn(move || server.serve(move |_, e| sender.lock().unwrap().send(e).unwrap()));
+
+    assert!(matches!(
+        Replica::connect(addr, &mut replica),
+        Err(Error::ChainsDiverged(1))
+    ));
+    let errors: Receiver<Error> = errors;
+    assert!(matches!(errors.recv().unwrap(), Error::ChainsDiverged(1)));
+}
+
+/// Accepts one connection on ```listener```, reads the request, and sends ```response```.
+fn respond(listener: TcpListener, response: Vec<u8>) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request: [u8; 44] = [0; 44];
+        stream.read_exact(&mut request).unwrap();
+        assert_eq!(&request[0..4], b"BCR1");
+        assert_eq!(request[4..12], 1u64.to_le_bytes());
+        stream.write_all(&response).unwrap();
+    })
+}
+
+#[test]
+fn responses_are_checked() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut replica: File =
+        common::create_chain(&dir.path().join("replica.blk"), 1, 16, Options::default()).unwrap();
+
+    let listener: TcpListener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr: SocketAddr = listener.local_addr().unwrap();
+    let mut response: Vec<u8> = vec![0];
+    response.extend_from_slice(&100u32.to_le_bytes());
+    response.extend_from_slice(&1u64.to_le_bytes());
+    let primary = respond(listener, response.clone());
+    assert!(matches!(
+        Replica::connect(addr, &mut replica),
+        Err(Error::BlockSizeMismatch(100, 48))
+    ));
+    primary.join().unwrap();
+
+    let listener: TcpListener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr: SocketAddr = listener.local_addr().unwrap();
+    response[0] = 9;
+    let primary = respond(listener, response);
+    assert!(matches!(
+        Replica::connect(addr, &mut replica),
+        Err(Error::InvalidMessage)
+    ));
+    primary.join().unwrap();
+}
+
+#[test]
+fn iteration_ends_when_the_primary_disconnects() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut replica: File =
+        common::create_chain(&dir.path().join("replica.blk"), 1, 16, Options::default()).unwrap();
+
+    let listener: TcpListener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr: SocketAddr = listener.local_addr().unwrap();
+    let mut response: Vec<u8> = vec![0];
+    response.extend_from_slice(&48u32.to_le_bytes());
+    response.extend_from_slice(&1u64.to_le_bytes());
+    let primary = respond(listener, response);
+    let mut replica: Replica = Replica::connect(addr, &mut replica).unwrap();
+    primary.join().unwrap();
+    assert!(matches!(replica.next(), Some(Err(Error::IOError(_)))));
+    assert!(replica.next().is_none());
+}
+
+#[test]
+fn partially_written_blocks_are_not_served() {
+    let dir = tempfile::tempdir().unwrap();
+    let primary_path = dir.path().join("primary.blk");
+    drop(common::create_chain(&primary_path, 3, 16, Options::default()).unwrap());
+    let mut replica: File =
+        common::create_chain(&dir.path().join("replica.blk"), 1, 16, Options::default()).unwrap();
+    // the primary is part way through appending block 3
+    std::fs::File::options()
+        .append(true)
+        .open(&primary_path)
+        .unwrap()
+        .write_all(&[0; 20])
+        .unwrap();
+
+    let server: Server = Server::bind(&primary_path, "127.0.0.1:0").unwrap();
+    let addr: SocketAddr = server.local_addr().unwrap();
+    thread::spawn(move || server.serve_one());
+
+    let mut replica: Replica = Replica::connect(addr, &mut replica).unwrap();
+    assert_eq!(replica.primary_count(), 3);
+    assert_eq!(replica.catch_up().unwrap(), 2);
+}
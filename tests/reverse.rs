@@ -0,0 +1,87 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+mod common;
+
+use bc_io::io::{Deserialize, File, Options, Reader, Result};
+
+/// The index at the start of a data section written by ```common::data()```.
+#[derive(Debug, PartialEq)]
+struct Index(u64);
+
+impl Deserialize for Index {
+    fn deserialize(buf: &[u8]) -> Result<Self> {
+        Ok(Index(u64::from_le_bytes(buf[0..8].try_into().unwrap())))
+    }
+}
+
+#[test]
+fn blocks_are_read_from_newest_to_oldest() {
+    let dir = tempfile::tempdir().unwrap();
+    // enough blocks to span several chunks
+    let mut file: File =
+        common::create_chain(&dir.path().join("chain.blk"), 150, 1024, Options::default()).unwrap();
+    let mut reader: Reader = Reader::new(&mut file);
+    let mut data: Vec<u8> = vec![0; 1024];
+    let mut expected: u64 = 150;
+    for block in reader.blocks_rev().unwrap() {
+        let (index, block) = block.unwrap();
+        expected -= 1;
+        assert_eq!(index, expected);
+        assert_eq!(&block[32..], common::data(index, 1024).as_slice());
+    }
+    assert_eq!(expected, 0);
+    reader.read_data_at(0, &mut data).unwrap();
+    assert_eq!(data, common::data(0, 1024));
+}
+
+#[test]
+fn last_n_stops_after_n_blocks() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut file: File =
+        common::create_chain(&dir.path().join("chain.blk"), 5, 16, Options::default()).unwrap();
+    let mut reader: Reader = Reader::new(&mut file);
+    let indexes: Vec<u64> = reader
+        .last_n(2)
+        .unwrap()
+        .map(|block| block.unwrap().0)
+        .collect();
+    assert_eq!(indexes, vec![4, 3]);
+    assert_eq!(reader.last_n(10).unwrap().count(), 5);
+    assert_eq!(reader.last_n(0).unwrap().count(), 0);
+
+    let records: Vec<(u64, Index)> = reader
+        .last_n_as::<Index>(3)
+        .unwrap()
+        .map(|record| record.unwrap())
+        .collect();
+    assert_eq!(records, vec![(4, Index(4)), (3, Index(3)), (2, Index(2))]);
+    assert_eq!(
+        reader
+            .blocks_rev_as::<Index>()
+            .unwrap()
+            .last()
+            .unwrap()
+            .unwrap(),
+        (0, Index(0))
+    );
+}
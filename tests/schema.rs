@@ -0,0 +1,135 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+use bc_io::io::{Deserialize, Error, File, Options, Reader, Result, Serialize, Writer};
+use bc_io::schema::{self, Registry, Versioned, VERSION_SIZE};
+
+/// The first version of a record, which only has an amount.
+struct V1 {
+    amount: u32,
+}
+
+/// The second version of a record, which adds a fee.
+#[derive(Debug, PartialEq)]
+struct V2 {
+    amount: u32,
+    fee: u16,
+}
+
+impl Serialize for V1 {
+    fn serialize(&self, buf: &mut [u8]) -> Result<()> {
+        buf[0..4].copy_from_slice(&self.amount.to_le_bytes());
+        buf[4..].fill(0);
+        Ok(())
+    }
+}
+
+impl Deserialize for V1 {
+    fn deserialize(buf: &[u8]) -> Result<Self> {
+        Ok(V1 {
+            amount: u32::from_le_bytes(buf[0..4].try_into().unwrap()),
+        })
+    }
+}
+
+impl Serialize for V2 {
+    fn serialize(&self, buf: &mut [u8]) -> Result<()> {
+        buf[0..4].copy_from_slice(&self.amount.to_le_bytes());
+        buf[4..6].copy_from_slice(&self.fee.to_le_bytes());
+        Ok(())
+    }
+}
+
+impl Deserialize for V2 {
+    fn deserialize(buf: &[u8]) -> Result<Self> {
+        Ok(V2 {
+            amount: u32::from_le_bytes(buf[0..4].try_into().unwrap()),
+            fee: u16::from_le_bytes(buf[4..6].try_into().unwrap()),
+        })
+    }
+}
+
+impl From<V1> for V2 {
+    fn from(v1: V1) -> Self {
+        V2 {
+            amount: v1.amount,
+            fee: 0,
+        }
+    }
+}
+
+#[test]
+fn every_version_decodes_to_the_latest() {
+    let dir = tempfile::tempdir().unwrap();
+    let size: usize = VERSION_SIZE + 6;
+    let mut file: File = File::create_new_with(
+        &dir.path().join("chain.blk"),
+        &mut Versioned::new(1, &V1 { amount: 5 }),
+        size,
+        Options::default(),
+    )
+    .unwrap();
+    {
+        let mut writer: Writer = Writer::new(&mut file).unwrap();
+        let mut data: Vec<u8> = vec![0; size];
+        Versioned::new(2, &V2 { amount: 7, fee: 1 })
+            .serialize(&mut data)
+            .unwrap();
+        assert_eq!(schema::version_of(&data).unwrap(), 2);
+        writer.append(&mut data).unwrap();
+        Versioned::new(3, &V2 { amount: 9, fee: 2 })
+            .serialize(&mut data)
+            .unwrap();
+        writer.append(&mut data).unwrap();
+    }
+
+    let mut registry: Registry<V2> = Registry::new();
+    registry.register::<V1>(1).register::<V2>(2);
+    assert_eq!(registry.versions(), vec![1, 2]);
+    let mut reader: Reader = Reader::new(&mut file);
+    assert_eq!(
+        registry.read_at(&mut reader, 0).unwrap(),
+        V2 { amount: 5, fee: 0 }
+    );
+    assert_eq!(
+        registry.read_at(&mut reader, 1).unwrap(),
+        V2 { amount: 7, fee: 1 }
+    );
+    assert!(matches!(
+        registry.read_at(&mut reader, 2),
+        Err(Error::UnknownVersion(3))
+    ));
+
+    registry.register_with(3, |record: V2| {
+        Ok(V2 {
+            amount: record.amount * 100,
+            fee: record.fee,
+        })
+    });
+    assert_eq!(
+        registry.read_at(&mut reader, 2).unwrap(),
+        V2 {
+            amount: 900,
+            fee: 2
+        }
+    );
+}
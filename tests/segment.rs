@@ -0,0 +1,118 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+mod common;
+
+use bc_hash::sha256::Digest;
+use bc_io::io::{Error, Options};
+use bc_io::segment::{Rollover, SegmentedChain};
+use std::path::Path;
+
+/// Creates a segmented chain at ```base``` with ```count``` blocks of 16 bytes.
+fn create(base: &Path, count: u64, rollover: Rollover) -> SegmentedChain {
+    let mut chain: SegmentedChain = SegmentedChain::create_new(
+        base,
+        &mut common::Raw(&common::data(0, 16)),
+        16,
+        Options::default(),
+        rollover,
+    )
+    .unwrap();
+    for index in 1..count {
+        chain.append(&mut common::data(index, 16)).unwrap();
+    }
+    chain
+}
+
+#[test]
+fn blocks_roll_over_into_new_segments() {
+    let dir = tempfile::tempdir().unwrap();
+    let base = dir.path().join("chain");
+    let chain: SegmentedChain = create(&base, 8, Rollover::Blocks(3));
+    assert_eq!(chain.segment_count(), 3);
+    assert_eq!(chain.block_count().unwrap(), 8);
+    let last_hash: Digest = chain.last_hash().clone();
+    drop(chain);
+    for number in 1..=3 {
+        assert!(SegmentedChain::segment_path(&base, number).exists());
+    }
+    assert!(!SegmentedChain::segment_path(&base, 4).exists());
+
+    let mut chain: SegmentedChain =
+        SegmentedChain::open_existing(&base, Rollover::Blocks(3)).unwrap();
+    assert_eq!(chain.segment_count(), 3);
+    assert_eq!(chain.block_count().unwrap(), 8);
+    assert_eq!(*chain.last_hash(), last_hash);
+    chain.validate_all_blocks().unwrap();
+    let mut data: Vec<u8> = vec![0; 16];
+    for index in 0..8 {
+        chain.read_data_at(index, &mut data).unwrap();
+        assert_eq!(data, common::data(index, 16));
+    }
+    assert!(matches!(
+        chain.read_data_at(8, &mut data),
+        Err(Error::BlockNumDoesNotExist)
+    ));
+    chain.append(&mut common::data(8, 16)).unwrap();
+    assert_eq!(chain.segment_count(), 3);
+    chain.validate_all_blocks().unwrap();
+}
+
+#[test]
+fn rollover_by_bytes_keeps_segments_under_the_limit() {
+    let dir = tempfile::tempdir().unwrap();
+    let base = dir.path().join("chain");
+    let chain: SegmentedChain = create(&base, 5, Rollover::Bytes(100));
+    assert_eq!(chain.segment_count(), 3);
+    for number in 1..=3 {
+        let size: u64 = std::fs::metadata(SegmentedChain::segment_path(&base, number))
+            .unwrap()
+            .len();
+        assert!(size <= 100);
+    }
+}
+
+#[test]
+fn broken_links_between_segments_are_detected() {
+    let dir = tempfile::tempdir().unwrap();
+    let base = dir.path().join("chain");
+    drop(create(&base, 5, Rollover::Blocks(3)));
+    common::corrupt(&SegmentedChain::segment_path(&base, 1), 2 * (32 + 16) + 40);
+    let mut chain: SegmentedChain =
+        SegmentedChain::open_existing(&base, Rollover::Blocks(3)).unwrap();
+    assert!(matches!(
+        chain.validate_all_blocks(),
+        Err(Error::InvalidBlockHash(3))
+    ));
+}
+
+#[test]
+fn readers_are_scoped_to_one_segment() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut chain: SegmentedChain = create(&dir.path().join("chain"), 8, Rollover::Blocks(3));
+    let (mut reader, index) = chain.reader(7).unwrap();
+    assert_eq!(index, 1);
+    assert_eq!(reader.block_count().unwrap(), 2);
+    let mut data: Vec<u8> = vec![0; 16];
+    reader.read_data_at(index, &mut data).unwrap();
+    assert_eq!(data, common::data(7, 16));
+}
@@ -0,0 +1,69 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+use bc_io::io::{Deserialize, Error, File, Reader, Writer};
+use bc_io::serde_bridge::{self, Serde, LENGTH_SIZE};
+
+type Record = (u32, String, Vec<i64>);
+
+#[test]
+fn serde_records_round_trip_through_a_chain() {
+    let dir = tempfile::tempdir().unwrap();
+    let genisis: Record = (1, String::from("genisis"), vec![]);
+    let mut file: File = File::create_new(
+        &dir.path().join("chain.blk"),
+        &mut Serde(genisis.clone()),
+        64,
+    )
+    .unwrap();
+    let record: Record = (2, String::from("transfer"), vec![-1, 300, i64::MAX]);
+    let mut data: Vec<u8> = vec![0; 64];
+    serde_bridge::encode(&record, &mut data).unwrap();
+    Writer::new(&mut file).unwrap().append(&mut data).unwrap();
+
+    let mut reader: Reader = Reader::new(&mut file);
+    reader.read_data_at(0, &mut data).unwrap();
+    assert_eq!(
+        Serde::<Record>::deserialize(&data).unwrap().into_inner(),
+        genisis
+    );
+    reader.read_data_at(1, &mut data).unwrap();
+    assert_eq!(serde_bridge::decode::<Record>(&data).unwrap(), record);
+}
+
+#[test]
+fn records_must_fit_in_the_data_section() {
+    let mut data: Vec<u8> = vec![0xff; 16];
+    let record: String = String::from("far too long for sixteen bytes");
+    assert!(matches!(
+        serde_bridge::encode(&record, &mut data),
+        Err(Error::RecordTooLarge(35, 16))
+    ));
+    serde_bridge::encode("short", &mut data).unwrap();
+    assert_eq!(&data[0..LENGTH_SIZE], &6u32.to_le_bytes());
+    assert!(data[LENGTH_SIZE + 6..].iter().all(|b| *b == 0));
+    data[0] = 20;
+    assert!(matches!(
+        serde_bridge::decode::<String>(&data),
+        Err(Error::InvalidEncoding)
+    ));
+}
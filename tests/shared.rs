@@ -0,0 +1,93 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+mod common;
+
+#[cfg(unix)]
+use bc_io::io::{Error, Options};
+#[cfg(unix)]
+use bc_io::shared::ChainFile;
+#[cfg(unix)]
+use std::sync::Arc;
+#[cfg(unix)]
+use std::thread;
+
+#[cfg(unix)]
+#[test]
+fn threads_append_and_read_through_one_handle() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("chain.blk");
+    let file = common::create_chain(&path, 1, 16, Options::default()).unwrap();
+    let chain: Arc<ChainFile> = Arc::new(ChainFile::new(file).unwrap());
+    let writers: Vec<thread::JoinHandle<()>> = (0..4u64)
+        .map(|thread| {
+            let chain: Arc<ChainFile> = chain.clone();
+            thread::spawn(move || {
+                for n in 0..25 {
+                    chain
+                        .append(&common::data(1000 * (thread + 1) + n, 16))
+                        .unwrap();
+                }
+            })
+        })
+        .collect();
+    let readers: Vec<thread::JoinHandle<()>> = (0..2)
+        .map(|_| {
+            let chain: Arc<ChainFile> = chain.clone();
+            thread::spawn(move || {
+                let mut data: Vec<u8> = vec![0; 16];
+                for _ in 0..50 {
+                    let index: u64 = chain.block_count() - 1;
+                    chain.read_data_at(index, &mut data).unwrap();
+                }
+            })
+        })
+        .collect();
+    for handle in writers.into_iter().chain(readers) {
+        handle.join().unwrap();
+    }
+
+    assert_eq!(chain.block_count(), 101);
+    chain.validate_all_blocks().unwrap();
+    let mut data: Vec<u8> = vec![0; 16];
+    let mut ids: Vec<u64> = (1..101)
+        .map(|index| {
+            chain.read_data_at(index, &mut data).unwrap();
+            u64::from_le_bytes(data[0..8].try_into().unwrap())
+        })
+        .collect();
+    ids.sort();
+    let expected: Vec<u64> = (1..=4)
+        .flat_map(|thread| (0..25).map(move |n| 1000 * thread + n))
+        .collect();
+    assert_eq!(ids, expected);
+    assert!(matches!(
+        chain.read_data_at(101, &mut data),
+        Err(Error::BlockNumDoesNotExist)
+    ));
+
+    let last_hash = chain.last_hash();
+    drop(chain);
+    let chain: ChainFile = ChainFile::open_existing(&path).unwrap();
+    assert_eq!(chain.block_count(), 101);
+    assert_eq!(chain.last_hash(), last_hash);
+}
@@ -0,0 +1,85 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+mod common;
+
+use bc_io::io::{Error, File, Options, Reader, Writer};
+use bc_io::signing::SigningKey;
+
+#[test]
+fn signed_blocks_are_verified_against_the_authorized_keys() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("chain.blk");
+    let key: SigningKey = SigningKey::from_bytes(&[7; 32]);
+    let other: SigningKey = SigningKey::from_bytes(&[8; 32]);
+    let options: Options = Options {
+        signing_key: Some(key.clone()),
+        ..Options::default()
+    };
+    drop(common::create_chain(&path, 3, 16, options).unwrap());
+
+    let mut file: File = File::open_existing(&path).unwrap();
+    assert!(matches!(
+        Reader::new(&mut file).validate_all_blocks(),
+        Err(Error::NoAuthorizedKeys)
+    ));
+    file.set_authorized_keys(vec![other.verifying_key()]);
+    assert!(matches!(
+        Reader::new(&mut file).validate_all_blocks(),
+        Err(Error::InvalidSignature(0))
+    ));
+    file.set_authorized_keys(vec![other.verifying_key(), key.verifying_key()]);
+    Reader::new(&mut file).validate_all_blocks().unwrap();
+    assert!(matches!(
+        Writer::new(&mut file)
+            .unwrap()
+            .append(&mut common::data(3, 16)),
+        Err(Error::MissingSigningKey)
+    ));
+    file.set_signing_key(key);
+    Writer::new(&mut file)
+        .unwrap()
+        .append(&mut common::data(3, 16))
+        .unwrap();
+    Reader::new(&mut file).validate_all_blocks().unwrap();
+}
+
+#[test]
+fn tampered_blocks_fail_verification() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("chain.blk");
+    let key: SigningKey = SigningKey::from_bytes(&[7; 32]);
+    let options: Options = Options {
+        signing_key: Some(key.clone()),
+        ..Options::default()
+    };
+    let block_size: usize = common::create_chain(&path, 3, 16, options)
+        .unwrap()
+        .block_size();
+    common::corrupt(&path, 2 * block_size + 40);
+    let mut file: File = File::open_existing(&path).unwrap();
+    file.set_authorized_keys(vec![key.verifying_key()]);
+    assert!(matches!(
+        Reader::new(&mut file).validate_all_blocks(),
+        Err(Error::InvalidSignature(2))
+    ));
+}
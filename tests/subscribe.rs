@@ -0,0 +1,46 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+mod common;
+
+use bc_hash::sha256::Digest;
+use bc_io::io::{File, Options, Writer};
+use std::sync::mpsc::Receiver;
+
+#[test]
+fn subscribers_receive_each_appended_block() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut file: File =
+        common::create_chain(&dir.path().join("chain.blk"), 1, 16, Options::default()).unwrap();
+    let mut writer: Writer = Writer::new(&mut file).unwrap();
+    let receiver: Receiver<(u64, Digest)> = writer.subscribe();
+    let dropped: Receiver<(u64, Digest)> = writer.subscribe();
+    drop(dropped);
+    for index in 1..3 {
+        writer.append(&mut common::data(index, 16)).unwrap();
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            (index, writer.last_hash().clone())
+        );
+    }
+    assert!(receiver.try_recv().is_err());
+}
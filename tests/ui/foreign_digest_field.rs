@@ -0,0 +1,12 @@
+use bc_io::io::Serialize;
+
+mod other {
+    pub struct Digest(pub [u8; 32]);
+}
+
+#[derive(Serialize)]
+struct Record {
+    root: other::Digest,
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use bc_io::io::Serialize;
+
+#[derive(Serialize)]
+struct Record {
+    count: usize,
+}
+
+fn main() {}
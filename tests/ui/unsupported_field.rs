@@ -0,0 +1,8 @@
+use bc_io::io::Deserialize;
+
+#[derive(Deserialize)]
+struct Record {
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,111 @@
+/// MIT License
+///
+/// Copyright (c) 2023 herrsmitty8128
+///
+/// Permission is hereby granted, free of charge, to any person obtaining a copy
+/// of this software and associated documentation files (the "Software"), to deal
+/// in the Software without restriction, including without limitation the rights
+/// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+/// copies of the Software, and to permit persons to whom the Software is
+/// furnished to do so, subject to the following conditions:
+///
+/// The above copyright notice and this permission notice shall be included in all
+/// copies or substantial portions of the Software.
+///
+/// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+/// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+/// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+/// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+/// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+/// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+/// SOFTWARE.
+
+mod common;
+
+use bc_hash::sha256::{Digest, DIGEST_SIZE};
+use bc_io::io::{Error, File, Options, Reader, Result};
+use bc_io::layout::Layout;
+use bc_io::view::{self, BlockView, View};
+
+/// The fields before the index in the data sections written by ```common::data()```.
+const INDEX: Layout = Layout::new();
+/// The fields before the filler in the data sections written by ```common::data()```.
+const FILLER: Layout = INDEX.u64();
+
+/// A typed view of the data sections written by ```common::data()```.
+struct Indexed<'a>(BlockView<'a>);
+
+impl<'a> View<'a> for Indexed<'a> {
+    const LAYOUT: Layout = FILLER.bytes(8);
+
+    fn from_view(view: BlockView<'a>) -> Self {
+        Indexed(view)
+    }
+}
+
+impl Indexed<'_> {
+    fn index(&self) -> Result<u64> {
+        self.0.get_u64_le(INDEX.offset())
+    }
+
+    fn filler(&self) -> Result<&[u8]> {
+        self.0.get_slice(FILLER.offset(), 8)
+    }
+}
+
+#[test]
+fn views_decode_fields_in_place() {
+    let dir = tempfile::tempdir().unwrap();
+    let options: Options = Options {
+        crc: true,
+        ..Options::default()
+    };
+    let mut file: File =
+        common::create_chain(&dir.path().join("chain.blk"), 4, 16, options).unwrap();
+    let mut reader: Reader = Reader::new(&mut file);
+    let mut block: Vec<u8> = vec![0; reader.block_size()];
+    reader.read_block_at(2, &mut block).unwrap();
+    let indexed: Indexed = BlockView::typed(&reader, &block).unwrap();
+    assert_eq!(indexed.index().unwrap(), 2);
+    assert_eq!(indexed.filler().unwrap(), &[2; 8]);
+    assert_eq!(indexed.0.data(), common::data(2, 16).as_slice());
+    assert!(matches!(
+        indexed.0.get_slice(FILLER.offset() + 1, 8),
+        Err(Error::InvalidSliceLength)
+    ));
+
+    let mut prev: [u8; DIGEST_SIZE] = [0; DIGEST_SIZE];
+    reader.read_block_at(1, &mut block).unwrap();
+    reader
+        .block_hash(&block)
+        .unwrap()
+        .serialize(&mut prev)
+        .unwrap();
+    reader.read_block_at(2, &mut block).unwrap();
+    let view: BlockView = BlockView::new(&reader, &block, &Indexed::LAYOUT).unwrap();
+    assert_eq!(
+        view.prev_hash().unwrap(),
+        Digest::deserialize(&prev).unwrap()
+    );
+}
+
+#[test]
+fn for_each_view_visits_every_block() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut file: File =
+        common::create_chain(&dir.path().join("chain.blk"), 5, 16, Options::default()).unwrap();
+    let mut reader: Reader = Reader::new(&mut file);
+    let mut indexes: Vec<u64> = Vec::new();
+    let count: u64 = view::for_each_view(&mut reader, &Indexed::LAYOUT, |index, view| {
+        assert_eq!(view.get_u64_le(INDEX.offset())?, index);
+        indexes.push(index);
+        Ok(())
+    })
+    .unwrap();
+    assert_eq!(count, 5);
+    assert_eq!(indexes, vec![0, 1, 2, 3, 4]);
+    assert!(matches!(
+        view::for_each_view(&mut reader, &Layout::new().u64(), |_, _| Ok(())),
+        Err(Error::InvalidSliceLength)
+    ));
+}